@@ -0,0 +1,38 @@
+//! Bulk-insert throughput for `PeerStore`, mirroring the insert benchmarks
+//! reference peer stores (e.g. garage's metadata table benches) ship
+//! alongside their storage layer so a schema/index change that regresses
+//! write throughput shows up before it reaches production.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use shard::peer_store::{PeerMetadataRecord, PeerStore};
+use tempfile::tempdir;
+
+fn bench_bulk_insert(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("peer_store_bulk_insert");
+
+    for count in [100usize, 1_000, 10_000] {
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.to_async(&rt).iter(|| async {
+                let dir = tempdir().unwrap();
+                let store = PeerStore::open(&dir.path().join("bench.db")).await.unwrap();
+                for i in 0..count {
+                    let record = PeerMetadataRecord {
+                        peer_id: format!("12D3KooWBenchPeer{i}"),
+                        first_seen_ms: 0,
+                        last_seen_ms: 0,
+                        addrs: vec![format!("/ip4/10.0.0.{}/tcp/4001", i % 255)],
+                        verified: i % 2 == 0,
+                        handshake_failures: 0,
+                    };
+                    store.upsert_peer_metadata(&record).await.unwrap();
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_bulk_insert);
+criterion_main!(benches);
@@ -0,0 +1,224 @@
+//! Resumable, content-addressed chunked tensor transfer, inspired by
+//! garage's block layer: `ForwardPassActivation`/`BackwardPassGradient`
+//! already carry a `TensorChunkRef` with `chunk_index`/`total_chunks` and a
+//! blake3 checksum, but gossipsub is the wrong transport for multi-megabyte
+//! activations. This module fetches/serves chunks by
+//! `(request_id, step_id, tensor_name, chunk_index)` over a dedicated
+//! request/response protocol, verifies each chunk against its checksum, and
+//! tracks which chunks are still missing so a partially received tensor can
+//! be resumed instead of re-fetched from scratch.
+
+use crate::TensorDataFormat;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Bounds how many chunk requests a receiver has outstanding at once for a
+/// single transfer, so one large tensor can't starve everything else.
+const MAX_OUTSTANDING_PER_TRANSFER: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkRequest {
+    pub request_id: String,
+    pub step_id: String,
+    pub tensor_name: String,
+    pub chunk_index: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkResponse {
+    pub chunk_index: u32,
+    /// `None` when the responder doesn't have this chunk (yet).
+    pub data: Option<String>,
+    pub checksum_blake3: Option<String>,
+}
+
+fn transfer_key(request_id: &str, step_id: &str, tensor_name: &str) -> String {
+    format!("{request_id}:{step_id}:{tensor_name}")
+}
+
+/// Verify base64-decoded chunk bytes against the declared blake3 checksum.
+pub fn verify_chunk(data_b64: &str, expected_checksum: &str) -> bool {
+    let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data_b64) else {
+        return false;
+    };
+    blake3::hash(&bytes).to_hex().to_string() == expected_checksum
+}
+
+struct Transfer {
+    request_id: String,
+    step_id: String,
+    tensor_name: String,
+    shape: Vec<usize>,
+    format: TensorDataFormat,
+    total_chunks: u32,
+    received: HashMap<u32, (String, String)>,
+    verified_count: u32,
+    /// Chunk indices currently in flight, tracked by index rather than a
+    /// bare count so a duplicate/unsolicited response (one whose index
+    /// isn't actually outstanding) can't free a slot it never held.
+    outstanding: HashSet<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransferStatus {
+    pub request_id: String,
+    pub step_id: String,
+    pub tensor_name: String,
+    pub total_chunks: u32,
+    pub verified_chunks: u32,
+    pub missing_chunks: Vec<u32>,
+}
+
+/// Tracks in-flight and partially-completed tensor transfers.
+#[derive(Default)]
+pub struct TransferManager {
+    transfers: HashMap<String, Transfer>,
+}
+
+impl TransferManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Begin (or re-register) a transfer. Safe to call repeatedly for the
+    /// same tensor — existing received chunks are preserved.
+    pub fn start(
+        &mut self,
+        request_id: &str,
+        step_id: &str,
+        tensor_name: &str,
+        shape: Vec<usize>,
+        format: TensorDataFormat,
+        total_chunks: u32,
+    ) {
+        let key = transfer_key(request_id, step_id, tensor_name);
+        self.transfers.entry(key).or_insert_with(|| Transfer {
+            request_id: request_id.to_string(),
+            step_id: step_id.to_string(),
+            tensor_name: tensor_name.to_string(),
+            shape,
+            format,
+            total_chunks,
+            received: HashMap::new(),
+            verified_count: 0,
+            outstanding: HashSet::new(),
+        });
+    }
+
+    /// Missing `chunk_index` values for a transfer, bounded by the
+    /// per-transfer flow-control budget so the caller doesn't issue more
+    /// outstanding requests than `MAX_OUTSTANDING_PER_TRANSFER` at once.
+    pub fn next_missing_chunks(
+        &mut self,
+        request_id: &str,
+        step_id: &str,
+        tensor_name: &str,
+    ) -> Vec<u32> {
+        let key = transfer_key(request_id, step_id, tensor_name);
+        let Some(transfer) = self.transfers.get_mut(&key) else {
+            return Vec::new();
+        };
+
+        let budget = MAX_OUTSTANDING_PER_TRANSFER.saturating_sub(transfer.outstanding.len());
+        let missing: Vec<u32> = (0..transfer.total_chunks)
+            .filter(|idx| !transfer.received.contains_key(idx) && !transfer.outstanding.contains(idx))
+            .take(budget)
+            .collect();
+        transfer.outstanding.extend(missing.iter().copied());
+        missing
+    }
+
+    /// Ingest a chunk fetched from a peer, verifying it against its declared
+    /// checksum. Returns `true` if the chunk verified and was stored.
+    pub fn ingest_chunk(
+        &mut self,
+        request_id: &str,
+        step_id: &str,
+        tensor_name: &str,
+        chunk_index: u32,
+        data_b64: &str,
+        checksum_blake3: &str,
+    ) -> bool {
+        let key = transfer_key(request_id, step_id, tensor_name);
+        let Some(transfer) = self.transfers.get_mut(&key) else {
+            return false;
+        };
+        transfer.outstanding.remove(&chunk_index);
+
+        if !verify_chunk(data_b64, checksum_blake3) {
+            return false;
+        }
+
+        if transfer
+            .received
+            .insert(chunk_index, (data_b64.to_string(), checksum_blake3.to_string()))
+            .is_none()
+        {
+            transfer.verified_count += 1;
+        }
+        true
+    }
+
+    /// Release an outstanding-request slot without storing a chunk, e.g.
+    /// when the peer we asked doesn't have it yet — lets
+    /// [`Self::next_missing_chunks`] re-issue that index to another peer.
+    pub fn release_outstanding(&mut self, request_id: &str, step_id: &str, tensor_name: &str, chunk_index: u32) {
+        let key = transfer_key(request_id, step_id, tensor_name);
+        if let Some(transfer) = self.transfers.get_mut(&key) {
+            transfer.outstanding.remove(&chunk_index);
+        }
+    }
+
+    pub fn is_complete(&self, request_id: &str, step_id: &str, tensor_name: &str) -> bool {
+        let key = transfer_key(request_id, step_id, tensor_name);
+        self.transfers
+            .get(&key)
+            .is_some_and(|t| t.verified_count >= t.total_chunks)
+    }
+
+    /// Borrow the shape/format declared for a transfer, for reassembly once
+    /// it's complete.
+    pub fn shape_and_format(
+        &self,
+        request_id: &str,
+        step_id: &str,
+        tensor_name: &str,
+    ) -> Option<(Vec<usize>, TensorDataFormat)> {
+        let key = transfer_key(request_id, step_id, tensor_name);
+        self.transfers
+            .get(&key)
+            .map(|t| (t.shape.clone(), t.format.clone()))
+    }
+
+    /// The raw chunk data and its blake3 checksum, if this node has already
+    /// verified and stored that chunk index.
+    pub fn chunk_for_serving(
+        &self,
+        request_id: &str,
+        step_id: &str,
+        tensor_name: &str,
+        chunk_index: u32,
+    ) -> Option<(String, String)> {
+        let key = transfer_key(request_id, step_id, tensor_name);
+        self.transfers
+            .get(&key)
+            .and_then(|t| t.received.get(&chunk_index).cloned())
+    }
+
+    pub fn statuses(&self) -> Vec<TransferStatus> {
+        self.transfers
+            .values()
+            .map(|t| TransferStatus {
+                request_id: t.request_id.clone(),
+                step_id: t.step_id.clone(),
+                tensor_name: t.tensor_name.clone(),
+                total_chunks: t.total_chunks,
+                verified_chunks: t.verified_count,
+                missing_chunks: (0..t.total_chunks)
+                    .filter(|idx| !t.received.contains_key(idx))
+                    .collect(),
+            })
+            .collect()
+    }
+}
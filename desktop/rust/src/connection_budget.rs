@@ -0,0 +1,112 @@
+//! Peer-excess / eviction ranking for inbound connections, layered on top of
+//! `libp2p::connection_limits::Behaviour` (which only hard-denies beyond a
+//! fixed ceiling — it has no notion of which peer is worth keeping).
+//! `excess_factor` lets the node carry a configurable overflow of
+//! outbound-only peers past the nominal inbound budget before this module
+//! picks the lowest-value *inbound* peer to prune, so a peer we deliberately
+//! dialed isn't evicted just because unsolicited inbound connections filled
+//! up first.
+
+use crate::{PeerInfo, ScoutPenaltyBook};
+use std::collections::HashMap;
+
+/// Connection-count limits enforced both at the swarm level
+/// (`libp2p::connection_limits::Behaviour`) and by this module's excess-peer
+/// eviction.
+#[derive(Debug, Clone)]
+pub struct ConnectionBudget {
+    pub max_total: u32,
+    pub max_pending_incoming: u32,
+    pub max_per_peer: u32,
+    pub excess_factor: f64,
+    /// Either a bare `PeerId` string or a full `Multiaddr` string ending in
+    /// `/p2p/<peer id>` — a full address lets [`ConnectionBudget::reserved_addrs`]
+    /// hand the reconnect loop something to dial.
+    pub reserved_peers: Vec<String>,
+    /// When set, inbound connections from peers outside `reserved_peers` are
+    /// dropped on establishment, pinning the node to a fixed backbone.
+    pub deny_unreserved: bool,
+}
+
+impl ConnectionBudget {
+    /// Inbound connections allowed before eviction kicks in — `max_total`
+    /// scaled down by the outbound overflow `excess_factor` reserves.
+    pub fn inbound_budget(&self) -> u32 {
+        ((self.max_total as f64) / self.excess_factor.max(1.0)).round() as u32
+    }
+
+    /// `true` for both a bare reserved `PeerId` and a reserved `Multiaddr`
+    /// that embeds this peer id.
+    pub fn is_reserved(&self, peer_id: &str) -> bool {
+        self.reserved_peers
+            .iter()
+            .any(|p| p == peer_id || p.contains(peer_id))
+    }
+
+    /// Pin a peer to the reserved backbone, by bare `PeerId` or by full
+    /// `Multiaddr` (so the reconnect loop has an address to dial). No-op if
+    /// already present.
+    pub fn add_reserved_peer(&mut self, addr_or_peer_id: String) {
+        if !self.reserved_peers.contains(&addr_or_peer_id) {
+            self.reserved_peers.push(addr_or_peer_id);
+        }
+    }
+
+    /// Drop a peer from the reserved backbone, matching both bare-id and
+    /// Multiaddr entries for it. Returns `true` if anything was removed.
+    pub fn remove_reserved_peer(&mut self, peer_id: &str) -> bool {
+        let before = self.reserved_peers.len();
+        self.reserved_peers.retain(|p| p != peer_id && !p.contains(peer_id));
+        self.reserved_peers.len() != before
+    }
+
+    pub fn set_deny_unreserved(&mut self, deny: bool) {
+        self.deny_unreserved = deny;
+    }
+
+    /// Reserved entries that look like dialable addresses, for the
+    /// reconnect loop to keep the backbone up even when a reserved peer
+    /// isn't (or isn't yet) in `known_peers`.
+    pub fn reserved_addrs(&self) -> Vec<String> {
+        self.reserved_peers
+            .iter()
+            .filter(|p| p.starts_with('/'))
+            .cloned()
+            .collect()
+    }
+}
+
+/// The lowest-value non-reserved peer to prune when connection count is over
+/// [`ConnectionBudget::inbound_budget`]: blackholed peers (per
+/// `scout_penalties`) first, then lowest gossipsub score, then lowest
+/// reported capacity.
+pub fn lowest_value_inbound_peer(
+    peers: &HashMap<String, PeerInfo>,
+    penalties: &mut ScoutPenaltyBook,
+    budget: &ConnectionBudget,
+) -> Option<String> {
+    let mut ranked: Vec<(&PeerInfo, bool)> = peers
+        .values()
+        .filter(|peer| peer.inbound)
+        .filter(|peer| !budget.is_reserved(&peer.peer_id))
+        .map(|peer| (peer, penalties.is_blackholed(&peer.peer_id)))
+        .collect();
+
+    ranked.sort_by(|(peer_a, blackholed_a), (peer_b, blackholed_b)| {
+        blackholed_b.cmp(blackholed_a).then_with(|| {
+            let score_a = peer_a.score.unwrap_or(0.0);
+            let score_b = peer_b.score.unwrap_or(0.0);
+            score_a
+                .partial_cmp(&score_b)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    peer_a
+                        .reported_capacity
+                        .unwrap_or(0)
+                        .cmp(&peer_b.reported_capacity.unwrap_or(0))
+                })
+        })
+    });
+
+    ranked.first().map(|(peer, _)| peer.peer_id.clone())
+}
@@ -0,0 +1,81 @@
+//! Capacity-aware direct work dispatch, modeled on murmel's per-peer download
+//! dispatcher: instead of fanning a `WorkRequest` out to every peer over
+//! gossipsub, rank connected/verified/non-blackholed peers by their gossiped
+//! `capacity`, `load`, and smoothed RTT and send the request directly to the
+//! best-fit peer over `control_work`, falling back to the next-best peer on
+//! timeout/failure. When no peer has reported capacity metadata yet, callers
+//! should fall back to the old broadcast-to-all behavior.
+
+use crate::{PeerInfo, ScoutPenaltyBook};
+use std::collections::HashMap;
+
+/// A ranked dispatch candidate: just enough to pick a peer and to retry the
+/// next-best one if the first attempt fails.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub peer_id: String,
+}
+
+/// Rank connected peers that are verified, not blackholed, and have reported
+/// capacity covering `min_tokens`, least-loaded (by `reported_load`) first,
+/// breaking ties by handshake failure count (a flapping peer falls back
+/// behind a stable one) and then by smoothed RTT (lower is better, unknown
+/// RTT sorts last). `rtt_of` is keyed by multiaddr (what `PeeringManager`
+/// actually tracks), not by peer id, so it's looked up per candidate via
+/// `PeerInfo::addrs`.
+pub fn rank_candidates(
+    peers: &HashMap<String, PeerInfo>,
+    penalties: &mut ScoutPenaltyBook,
+    rtt_of: impl Fn(&str) -> Option<f64>,
+    min_tokens: i32,
+) -> Vec<Candidate> {
+    let mut candidates: Vec<(&PeerInfo, f64)> = peers
+        .values()
+        .filter(|peer| peer.verified)
+        .filter(|peer| !penalties.is_blackholed(&peer.peer_id))
+        .filter(|peer| {
+            peer.reported_capacity
+                .is_some_and(|capacity| capacity as i32 >= min_tokens)
+        })
+        .map(|peer| {
+            let load = peer.reported_load.unwrap_or(0) as f64;
+            (peer, load)
+        })
+        .collect();
+
+    let best_rtt = |peer: &PeerInfo| -> f64 {
+        peer.addrs
+            .iter()
+            .filter_map(|addr| rtt_of(addr.as_str()))
+            .fold(f64::MAX, f64::min)
+    };
+
+    candidates.sort_by(|(peer_a, load_a), (peer_b, load_b)| {
+        load_a
+            .partial_cmp(load_b)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| peer_a.handshake_failures.cmp(&peer_b.handshake_failures))
+            .then_with(|| {
+                best_rtt(peer_a)
+                    .partial_cmp(&best_rtt(peer_b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    });
+
+    candidates
+        .into_iter()
+        .map(|(peer, _)| Candidate {
+            peer_id: peer.peer_id.clone(),
+        })
+        .collect()
+}
+
+/// Remaining candidates to try after a dispatch to `failed_peer_id` timed out
+/// or otherwise failed, in ranked order, excluding the one that just failed.
+pub fn next_after_failure(candidates: &[Candidate], failed_peer_id: &str) -> Vec<Candidate> {
+    candidates
+        .iter()
+        .filter(|c| c.peer_id != failed_peer_id)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,112 @@
+//! IP/CIDR-level connection blacklist, layered on top of `ScoutPenaltyBook`'s
+//! peer_id-keyed blackholing: a `PeerId` is free to regenerate, but the
+//! underlying address usually isn't, so when a scout gets blackholed by
+//! peer_id this module can also temporarily ban the IPs it connected from,
+//! closing the re-keying loophole.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+/// A parsed blacklist entry: either a single address or a CIDR network.
+#[derive(Debug, Clone, Copy)]
+pub enum CidrEntry {
+    Exact(IpAddr),
+    Network { addr: IpAddr, prefix_len: u8 },
+}
+
+impl CidrEntry {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match self {
+            CidrEntry::Exact(entry_ip) => *entry_ip == ip,
+            CidrEntry::Network { addr, prefix_len } => match (addr, ip) {
+                (IpAddr::V4(net), IpAddr::V4(candidate)) => {
+                    let mask = (u32::MAX).checked_shl(32 - *prefix_len as u32).unwrap_or(0);
+                    (u32::from(*net) & mask) == (u32::from(candidate) & mask)
+                }
+                (IpAddr::V6(net), IpAddr::V6(candidate)) => {
+                    let mask = (u128::MAX).checked_shl(128 - *prefix_len as u32).unwrap_or(0);
+                    (u128::from(*net) & mask) == (u128::from(candidate) & mask)
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Parse a config entry of the form `IP`, `IP:PORT`, or `IP/prefixlen`
+/// (CIDRv4/v6). Returns `None` for anything else so the caller can warn and
+/// skip rather than fail startup over one bad entry.
+pub fn parse_entry(raw: &str) -> Option<CidrEntry> {
+    let raw = raw.trim();
+    if let Some((addr, prefix)) = raw.rsplit_once('/') {
+        let addr: IpAddr = addr.parse().ok()?;
+        let prefix_len: u8 = prefix.parse().ok()?;
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max_prefix {
+            return None;
+        }
+        return Some(CidrEntry::Network { addr, prefix_len });
+    }
+    if let Ok(addr) = raw.parse::<IpAddr>() {
+        return Some(CidrEntry::Exact(addr));
+    }
+    if let Ok(socket) = raw.parse::<std::net::SocketAddr>() {
+        return Some(CidrEntry::Exact(socket.ip()));
+    }
+    None
+}
+
+/// Extract the IP component of a libp2p remote address string like
+/// `/ip4/1.2.3.4/tcp/4001` or `/ip6/::1/tcp/4001/ws`.
+pub fn extract_ip_from_multiaddr(remote_addr: &str) -> Option<IpAddr> {
+    let mut parts = remote_addr.split('/').filter(|s| !s.is_empty());
+    while let Some(part) = parts.next() {
+        if part == "ip4" || part == "ip6" {
+            return parts.next()?.parse().ok();
+        }
+    }
+    None
+}
+
+/// Static (config-loaded) CIDR/IP entries plus a temporary, expiring
+/// auto-escalation list keyed by exact IP.
+#[derive(Default)]
+pub struct IpBlacklist {
+    static_entries: Vec<CidrEntry>,
+    temporary_bans: HashMap<IpAddr, u128>,
+}
+
+impl IpBlacklist {
+    pub fn new(static_entries: Vec<CidrEntry>) -> Self {
+        Self {
+            static_entries,
+            temporary_bans: HashMap::new(),
+        }
+    }
+
+    /// `true` if `ip` matches a static entry or an unexpired temporary ban;
+    /// lazily evicts an expired ban it encounters along the way.
+    pub fn is_blocked(&mut self, ip: IpAddr, now_ms: u128) -> bool {
+        if self.static_entries.iter().any(|entry| entry.contains(ip)) {
+            return true;
+        }
+        match self.temporary_bans.get(&ip) {
+            Some(until) if *until > now_ms => true,
+            Some(_) => {
+                self.temporary_bans.remove(&ip);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Auto-escalation hook: temporarily ban `ip` until `until_ms`, e.g.
+    /// when the peer_id that connected from it just got blackholed.
+    /// Extends an existing ban rather than shortening it.
+    pub fn escalate(&mut self, ip: IpAddr, until_ms: u128) {
+        self.temporary_bans
+            .entry(ip)
+            .and_modify(|existing| *existing = (*existing).max(until_ms))
+            .or_insert(until_ms);
+    }
+}
@@ -0,0 +1,10 @@
+//! Thin library surface for the `shard` bin crate.
+//!
+//! Nearly all of this crate's logic lives in `main.rs` as a single binary;
+//! this lib target exists so modules that need to be exercised from a
+//! separate compilation unit — `benches/peer_store_bench.rs`, which can't
+//! reach into a bin crate's private modules — have a stable, public entry
+//! point. `main.rs` pulls `peer_store` back in via `use shard::peer_store;`
+//! rather than declaring its own private copy of the module.
+
+pub mod peer_store;
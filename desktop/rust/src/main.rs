@@ -11,14 +11,15 @@
 
 use anyhow::Result;
 use axum::{
-    extract::State as AxumState,
+    extract::{Path as AxumPath, State as AxumState},
     http::Method,
-    routing::{get, post},
+    response::IntoResponse,
+    routing::{delete, get, post},
     Json, Router,
 };
 use clap::Parser;
 use libp2p::{
-    autonat, dcutr,
+    autonat, connection_limits, dcutr,
     futures::StreamExt,
     gossipsub::{self, IdentTopic, MessageAuthenticity},
     identify, identity,
@@ -28,7 +29,9 @@ use libp2p::{
     swarm::{NetworkBehaviour, SwarmEvent},
     Multiaddr, PeerId, StreamProtocol,
 };
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
+use shard::peer_store;
 use std::{
     collections::{HashMap, HashSet, VecDeque},
     net::SocketAddr,
@@ -42,6 +45,13 @@ use std::{
 use tokio::sync::{mpsc, Mutex};
 use tower_http::cors::{Any, CorsLayer};
 
+mod block_transfer;
+mod connection_budget;
+mod dispatch;
+mod ip_blacklist;
+mod peer_manager;
+mod peer_sampling;
+mod peering;
 mod telemetry_ws;
 
 // ─── CLI ────────────────────────────────────────────────────────────────────
@@ -57,6 +67,14 @@ struct Cli {
     #[arg(long, default_value = "9093")]
     telemetry_ws_port: u16,
 
+    /// PEM certificate chain for serving telemetry as wss:// (requires telemetry_tls_key)
+    #[arg(long)]
+    telemetry_tls_cert: Option<String>,
+
+    /// PEM private key for serving telemetry as wss:// (requires telemetry_tls_cert)
+    #[arg(long)]
+    telemetry_tls_key: Option<String>,
+
     /// TCP transport listen port
     #[arg(long, default_value = "4001")]
     tcp_port: u16,
@@ -104,6 +122,54 @@ struct Cli {
     /// Enable NAT traversal (circuit relay + hole punching)
     #[arg(long, default_value = "true")]
     nat_traversal: bool,
+
+    /// Gossipsub peer score below which a peer is no longer favored for
+    /// mesh inclusion
+    #[arg(long, default_value = "0.0")]
+    gossipsub_score_gossip_threshold: f64,
+
+    /// Gossipsub peer score below which the daemon stops publishing to a peer
+    #[arg(long, default_value = "-50.0")]
+    gossipsub_score_publish_threshold: f64,
+
+    /// Gossipsub peer score below which a peer is graylisted (ignored
+    /// entirely) and penalized in `scout_penalties`
+    #[arg(long, default_value = "-80.0")]
+    gossipsub_score_graylist_threshold: f64,
+
+    /// Gossipsub peer score required to accept peer-exchange info from a peer
+    #[arg(long, default_value = "10.0")]
+    gossipsub_score_accept_px_threshold: f64,
+
+    /// Maximum total established connections
+    #[arg(long, default_value = "200")]
+    max_connections: u32,
+
+    /// Maximum pending incoming connections (dialed but not yet established)
+    #[arg(long, default_value = "50")]
+    max_pending_incoming: u32,
+
+    /// Maximum established connections to a single peer (across transports)
+    #[arg(long, default_value = "1")]
+    max_connections_per_peer: u32,
+
+    /// Ratio of `max_connections` to the inbound budget: once inbound
+    /// connections exceed `max_connections / factor`, the lowest-value
+    /// inbound peer is pruned, and the rest of `max_connections` stays open
+    /// for outbound-only peers, e.g. 1.25 allows 25% overflow over the
+    /// inbound budget
+    #[arg(long, default_value = "1.25")]
+    connection_excess_factor: f64,
+
+    /// PeerId that is never pruned by peer-excess eviction, even when over
+    /// budget (can be repeated)
+    #[arg(long)]
+    reserved_peer: Vec<String>,
+
+    /// IP/CIDR to reject connections from: `IP`, `IP:PORT`, or
+    /// `IP/prefixlen` (can be repeated)
+    #[arg(long)]
+    ip_blacklist: Vec<String>,
 }
 
 // ─── Protocol Messages ─────────────────────────────────────────────────────
@@ -114,6 +180,94 @@ struct Heartbeat {
     sent_at_ms: u128,
 }
 
+/// Capability attestation: what a peer claims about itself
+/// (`capacity`/`supported_formats`/`model_version`), signed with its libp2p
+/// Ed25519 identity key so the claim can be checked against the connecting
+/// `PeerId` instead of trusted at face value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CapabilityAttestation {
+    capacity: u32,
+    supported_formats: Vec<TensorDataFormat>,
+    model_version: String,
+    sent_at_ms: u128,
+    /// Protobuf-encoded libp2p public key the signature was produced with.
+    public_key_proto: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+/// The `handshake` protocol carries four message kinds: a signed capability
+/// attestation (authenticates advertised capacity and sets `verified`), a
+/// cheap unsigned liveness ping that never touches `verified`, and a
+/// `GetPeers`/`Peers` pair that lets a node bootstrap beyond its seed list by
+/// asking an already-connected peer for a sample of its address book.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum HandshakeMessage {
+    Ping(Heartbeat),
+    Attestation(CapabilityAttestation),
+    /// Ask the peer for a sample of the addresses in its `known_peers`.
+    GetPeers,
+    /// Response to `GetPeers`: a capped, IP-diverse random sample of the
+    /// responder's `known_peers`, excluding the requester's own addresses
+    /// and any address belonging to a currently-blackholed peer.
+    Peers(Vec<String>),
+}
+
+/// Canonical bytes signed/verified for a capability attestation — everything
+/// except the signature itself and the public key used to produce it.
+fn capability_attestation_signing_bytes(
+    capacity: u32,
+    supported_formats: &[TensorDataFormat],
+    model_version: &str,
+    sent_at_ms: u128,
+) -> Vec<u8> {
+    serde_json::to_vec(&(capacity, supported_formats, model_version, sent_at_ms))
+        .expect("capability attestation payload is always serializable")
+}
+
+/// Build and sign a capability attestation for this node using its libp2p
+/// identity key.
+fn sign_capability_attestation(
+    id_keys: &identity::Keypair,
+    capacity: u32,
+    supported_formats: Vec<TensorDataFormat>,
+    model_version: String,
+) -> CapabilityAttestation {
+    let sent_at_ms = now_ms();
+    let payload =
+        capability_attestation_signing_bytes(capacity, &supported_formats, &model_version, sent_at_ms);
+    let signature = id_keys
+        .sign(&payload)
+        .expect("ed25519 signing does not fail");
+    CapabilityAttestation {
+        capacity,
+        supported_formats,
+        model_version,
+        sent_at_ms,
+        public_key_proto: id_keys.public().encode_protobuf(),
+        signature,
+    }
+}
+
+/// Verify a capability attestation's signature against the `PeerId` we
+/// received it from, rejecting any claim whose embedded public key doesn't
+/// hash to the connecting peer or whose signature doesn't check out.
+fn verify_capability_attestation(attestation: &CapabilityAttestation, from: &PeerId) -> bool {
+    let Ok(public_key) = identity::PublicKey::try_decode_protobuf(&attestation.public_key_proto)
+    else {
+        return false;
+    };
+    if PeerId::from(public_key.clone()) != *from {
+        return false;
+    }
+    let payload = capability_attestation_signing_bytes(
+        attestation.capacity,
+        &attestation.supported_formats,
+        &attestation.model_version,
+        attestation.sent_at_ms,
+    );
+    public_key.verify(&payload, &attestation.signature)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct DraftSubmission {
     task_id: String,
@@ -139,6 +293,11 @@ pub struct WorkResponse {
     pub latency_ms: f32,
     #[serde(default)]
     pub created_at_ms: Option<u128>,
+    /// The responding scout's active request count at send time, fed into
+    /// `PeerInfo::reported_load` so `dispatch::rank_candidates` has a live
+    /// load signal instead of the field sitting permanently `None`.
+    #[serde(default)]
+    pub current_load: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +309,15 @@ pub enum TensorDataFormat {
     Quantized,
 }
 
+/// Tensor formats this build can serve/consume, advertised in our own
+/// capability attestations.
+const SUPPORTED_TENSOR_FORMATS: &[TensorDataFormat] = &[
+    TensorDataFormat::Fp16,
+    TensorDataFormat::Fp32,
+    TensorDataFormat::Bf16,
+    TensorDataFormat::Quantized,
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TensorChunkRef {
     pub chunk_index: u32,
@@ -242,22 +410,70 @@ struct PeerInfo {
     addrs: Vec<String>,
     verified: bool,
     handshake_failures: u32,
+    /// Throughput the peer has advertised, populated from a signed
+    /// capability attestation once its signature has been verified against
+    /// its `PeerId` — never trusted from an unauthenticated claim.
+    #[serde(default)]
+    reported_capacity: Option<u32>,
+    /// Active request count the peer last reported on a `WorkResponse`'s
+    /// `current_load`, unauthenticated (unlike `reported_capacity`, there's
+    /// no signature to check — a misreport only skews our own dispatch
+    /// ranking, not the peer's reputation). `None` until a gossiped
+    /// `WorkResponse` from it has carried a `current_load`.
+    #[serde(default)]
+    reported_load: Option<u32>,
+    /// Most recent gossipsub peer score observed via
+    /// `gossipsub::Behaviour::peer_score`, refreshed on `score_tick`. `None`
+    /// until the peer has participated in scored topics long enough for
+    /// gossipsub to report a score.
+    #[serde(default)]
+    score: Option<f64>,
+    /// Outcome of the most recent DCUtR hole-punch attempt to this peer.
+    /// `None` until a punch has been attempted; `Some(true)` lets the
+    /// reconnect loop prefer dialing this peer directly over redialing a
+    /// relayed (`/p2p-circuit`) address for it.
+    #[serde(default)]
+    hole_punch_succeeded: Option<bool>,
+    /// Whether this connection was accepted from a remote dialer (`true`) or
+    /// initiated by us (`false`), so peer-excess eviction only ever prunes
+    /// unsolicited inbound connections.
+    #[serde(default)]
+    inbound: bool,
 }
 
 #[derive(Clone)]
 struct SharedState {
     topology: Arc<Mutex<TopologyState>>,
-    peers: Arc<Mutex<HashMap<String, PeerInfo>>>,
-    known_peers: Arc<Mutex<Vec<String>>>,
+    /// Connected-peer state, known addresses, and scout reputation, behind
+    /// one lock — see `peer_manager::PeerManager` for why these three used
+    /// to be separate `Mutex`es and no longer are.
+    peer_manager: Arc<Mutex<peer_manager::PeerManager>>,
     results: Arc<Mutex<VecDeque<WorkResponse>>>,
+    /// `WorkRequest`s this node received (and validated) off the
+    /// `shard-work` gossipsub topic, queued here for the local scout driver
+    /// to poll via `/pop-work` — the gossipsub side only disseminates and
+    /// scores messages, it never executes a draft itself.
+    incoming_work: Arc<Mutex<VecDeque<WorkRequest>>>,
     work_tx: mpsc::Sender<WorkRequest>,
     daemon_start: u128,
     capacity: Arc<AtomicU32>,
     current_load: Arc<AtomicU32>,
     avg_latency_ms: Arc<AtomicU32>,
     gossipsub_latency_hist: Arc<LatencyHistogram>,
-    scout_penalties: Arc<Mutex<ScoutPenaltyBook>>,
+    /// Propagation latency for `WorkRequest`s received over the `shard-work`
+    /// topic, tracked separately from `gossipsub_latency_hist` (which times
+    /// `WorkResponse`s on `shard-work-result`) so the two directions of
+    /// traffic don't get blended into one histogram.
+    work_request_latency_hist: Arc<LatencyHistogram>,
     backward_passes: Arc<Mutex<VecDeque<BackwardPassGradient>>>,
+    telemetry_history: telemetry_ws::TelemetryHistory,
+    sampling_view: Arc<Mutex<peer_sampling::SamplingView>>,
+    peering: Arc<Mutex<peering::PeeringManager>>,
+    transfers: Arc<Mutex<block_transfer::TransferManager>>,
+    bandwidth: Arc<BandwidthMeter>,
+    connection_budget: Arc<Mutex<connection_budget::ConnectionBudget>>,
+    peer_store: Arc<peer_store::PeerStore>,
+    ip_blacklist: Arc<Mutex<ip_blacklist::IpBlacklist>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -278,15 +494,45 @@ struct ScoutPenaltyStatus {
     blackholed: bool,
     success_rate: f32,
     last_reason: Option<String>,
+    /// Mirrors `ScoutReputationEntry::banned_until_ms`, so callers can write
+    /// the exact ban expiry through to the peer store.
+    banned_until_ms: Option<u128>,
+    /// Mirrors `ScoutReputationEntry::recent_bans`, so the escalating-ban-
+    /// duration counter survives a restart instead of reverting to the base
+    /// cooldown for a peer that's already a repeat offender.
+    recent_bans: u32,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct ScoutReputationEntry {
     recent: VecDeque<bool>,
     failure_count: u32,
     accepted_count: u32,
     banned_until_ms: Option<u128>,
     last_reason: Option<String>,
+    /// Time-decayed reputation score, drifting back toward
+    /// [`ScoutPenaltyBook::BASELINE_SCORE`] between updates so an idle
+    /// peer's score isn't frozen at whatever it last was.
+    score: f32,
+    last_update_ms: u128,
+    /// Count of bans this peer has served; each fresh ban's duration grows
+    /// with this, so repeat offenders are banned longer each time.
+    recent_bans: u32,
+}
+
+impl Default for ScoutReputationEntry {
+    fn default() -> Self {
+        Self {
+            recent: VecDeque::new(),
+            failure_count: 0,
+            accepted_count: 0,
+            banned_until_ms: None,
+            last_reason: None,
+            score: ScoutPenaltyBook::BASELINE_SCORE,
+            last_update_ms: 0,
+            recent_bans: 0,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -298,7 +544,17 @@ impl ScoutPenaltyBook {
     const WINDOW_SIZE: usize = 10;
     const MIN_SAMPLES_FOR_BAN: usize = 5;
     const SUCCESS_RATE_THRESHOLD: f32 = 0.55;
-    const BAN_COOLDOWN_MS: u128 = 60_000;
+    const BASE_BAN_COOLDOWN_MS: u128 = 60_000;
+    const MAX_BAN_COOLDOWN_MS: u128 = 60 * 60_000;
+    /// Neutral starting point a peer's score decays toward between updates.
+    const BASELINE_SCORE: f32 = 50.0;
+    /// Score shift applied on top of decay for each accept/reject.
+    const SCORE_DELTA: f32 = 10.0;
+    /// Half-life of the decay toward `BASELINE_SCORE`.
+    const SCORE_HALF_LIFE_MS: u128 = 5 * 60_000;
+    /// Reduced score a peer re-enters on once its ban expires, rather than
+    /// whatever stale score it had when it was banned.
+    const PROBATION_SCORE: f32 = 30.0;
 
     fn success_rate(entry: &ScoutReputationEntry) -> f32 {
         if entry.recent.is_empty() {
@@ -308,10 +564,22 @@ impl ScoutPenaltyBook {
         success / (entry.recent.len() as f32)
     }
 
+    /// Decay `entry.score` toward `BASELINE_SCORE` for the time elapsed
+    /// since its last update, then advance `last_update_ms` to `now`.
+    fn decay_score(entry: &mut ScoutReputationEntry, now: u128) {
+        let elapsed = now.saturating_sub(entry.last_update_ms);
+        let half_lives = elapsed as f64 / Self::SCORE_HALF_LIFE_MS as f64;
+        let decay = 0.5f64.powf(half_lives) as f32;
+        entry.score = Self::BASELINE_SCORE + (entry.score - Self::BASELINE_SCORE) * decay;
+        entry.last_update_ms = now;
+    }
+
     fn apply_update(&mut self, update: ScoutPenaltyUpdate) -> ScoutPenaltyStatus {
         let now = now_ms();
         let entry = self.entries.entry(update.peer_id.clone()).or_default();
 
+        Self::decay_score(entry, now);
+
         if entry.recent.len() >= Self::WINDOW_SIZE {
             entry.recent.pop_front();
         }
@@ -319,18 +587,27 @@ impl ScoutPenaltyBook {
 
         if update.accepted {
             entry.accepted_count = entry.accepted_count.saturating_add(1);
+            entry.score = (entry.score + Self::SCORE_DELTA).clamp(0.0, 100.0);
         } else {
             entry.failure_count = entry.failure_count.saturating_add(1);
+            entry.score = (entry.score - Self::SCORE_DELTA).clamp(0.0, 100.0);
             if let Some(reason) = update.reason.as_ref() {
                 entry.last_reason = Some(reason.clone());
             }
         }
 
         let success_rate = Self::success_rate(entry);
-        if entry.recent.len() >= Self::MIN_SAMPLES_FOR_BAN
+        let already_banned = entry.banned_until_ms.map(|until| until > now).unwrap_or(false);
+        if !already_banned
+            && entry.recent.len() >= Self::MIN_SAMPLES_FOR_BAN
             && success_rate < Self::SUCCESS_RATE_THRESHOLD
         {
-            entry.banned_until_ms = Some(now + Self::BAN_COOLDOWN_MS);
+            let growth = 1u128 << entry.recent_bans.min(16);
+            let ban_ms = Self::BASE_BAN_COOLDOWN_MS
+                .saturating_mul(growth)
+                .min(Self::MAX_BAN_COOLDOWN_MS);
+            entry.banned_until_ms = Some(now + ban_ms);
+            entry.recent_bans = entry.recent_bans.saturating_add(1);
         }
 
         let blackholed = entry
@@ -344,15 +621,37 @@ impl ScoutPenaltyBook {
 
         ScoutPenaltyStatus {
             peer_id: update.peer_id.clone(),
-            score: (success_rate * 100.0).round() as i32,
+            score: entry.score.round() as i32,
             failures: entry.failure_count,
             accepted: entry.accepted_count,
             blackholed,
             success_rate,
             last_reason: entry.last_reason.clone(),
+            banned_until_ms: entry.banned_until_ms,
+            recent_bans: entry.recent_bans,
         }
     }
 
+    /// Restore a peer's reputation from a [`peer_store::PenaltyRecord`] on
+    /// startup. `recent` is left empty — the rolling accept/reject window
+    /// isn't persisted, only its cumulative counts and ban state — so the
+    /// success rate shown for a freshly-rehydrated entry is `1.0` until new
+    /// samples arrive, while `is_blackholed` still honors the restored ban.
+    fn rehydrate(&mut self, record: &peer_store::PenaltyRecord) {
+        let entry = self.entries.entry(record.peer_id.clone()).or_default();
+        entry.accepted_count = record.accepted_count;
+        entry.failure_count = record.failure_count;
+        entry.banned_until_ms = record.banned_until_ms;
+        entry.last_reason = record.last_reason.clone();
+        entry.score = record.score;
+        entry.recent_bans = record.recent_bans;
+        entry.last_update_ms = now_ms();
+    }
+
+    /// `true` while `peer_id` is still within its ban window. Once the ban
+    /// has expired, clears it and drops the peer's score to
+    /// `PROBATION_SCORE` so it re-enters on probation rather than picking
+    /// back up wherever its (possibly high, now-stale) score left off.
     fn is_blackholed(&mut self, peer_id: &str) -> bool {
         let now = now_ms();
         if let Some(entry) = self.entries.get_mut(peer_id) {
@@ -361,17 +660,32 @@ impl ScoutPenaltyBook {
                     return true;
                 }
                 entry.banned_until_ms = None;
+                entry.score = Self::PROBATION_SCORE;
+                entry.last_update_ms = now;
             }
         }
         false
     }
 
+    /// Side-effect-free equivalent of [`Self::is_blackholed`] for callers
+    /// (the connection-gating check in `should_reject_peer_connection`)
+    /// that need to ask "is this peer banned right now?" without also
+    /// performing the probation-reset write on expiry — that reset still
+    /// happens lazily the next time something calls the mutating
+    /// `is_blackholed` (e.g. dispatch ranking, gossipsub message handling).
+    fn is_banned(&self, peer_id: &str) -> bool {
+        self.entries
+            .get(peer_id)
+            .and_then(|entry| entry.banned_until_ms)
+            .is_some_and(|until| until > now_ms())
+    }
+
     fn all_statuses(&self) -> Vec<ScoutPenaltyStatus> {
         self.entries
             .iter()
             .map(|(peer_id, entry)| ScoutPenaltyStatus {
                 peer_id: peer_id.clone(),
-                score: (Self::success_rate(entry) * 100.0).round() as i32,
+                score: entry.score.round() as i32,
                 failures: entry.failure_count,
                 accepted: entry.accepted_count,
                 blackholed: entry
@@ -380,13 +694,117 @@ impl ScoutPenaltyBook {
                     .unwrap_or(false),
                 success_rate: Self::success_rate(entry),
                 last_reason: entry.last_reason.clone(),
+                banned_until_ms: entry.banned_until_ms,
+                recent_bans: entry.recent_bans,
             })
             .collect()
     }
 }
 
-fn should_reject_peer_connection(penalties: &mut ScoutPenaltyBook, peer_id: &str) -> bool {
-    penalties.is_blackholed(peer_id)
+/// Pure read against `PeerManager`: `true` if `peer_id` is currently
+/// blackholed. Deliberately uses `PeerManager::is_banned` rather than
+/// `is_blackholed` — the latter resets a peer's score off probation on ban
+/// expiry as a side effect, which this transport-layer gate has no business
+/// triggering just by being asked a yes/no question.
+fn should_reject_peer_connection(pm: &peer_manager::PeerManager, peer_id: &str) -> bool {
+    pm.is_banned(peer_id)
+}
+
+/// Write a scout's updated reputation through to `peer_store` in the
+/// background, so `ScoutPenaltyBook::apply_update` never blocks the swarm
+/// loop on disk I/O.
+async fn persist_scout_status(state: &SharedState, status: &ScoutPenaltyStatus) {
+    let peer_store = state.peer_store.clone();
+    let record = peer_store::PenaltyRecord {
+        peer_id: status.peer_id.clone(),
+        accepted_count: status.accepted,
+        failure_count: status.failures,
+        score: status.score as f32,
+        banned_until_ms: status.banned_until_ms,
+        last_reason: status.last_reason.clone(),
+        recent_bans: status.recent_bans,
+    };
+    tokio::spawn(async move {
+        if let Err(err) = peer_store.upsert_penalty(&record).await {
+            tracing::warn!(%err, "failed to persist scout penalty to peer store");
+        }
+    });
+
+    if status.blackholed {
+        if let Some(until_ms) = status.banned_until_ms {
+            escalate_ip_ban(state, &status.peer_id, until_ms).await;
+        }
+    }
+}
+
+/// When a scout gets blackholed by peer_id, also temporarily ban the IPs
+/// it's known to have connected from, so a fresh `PeerId` from the same
+/// address doesn't walk straight back in.
+async fn escalate_ip_ban(state: &SharedState, peer_id: &str, until_ms: u128) {
+    let addrs = state
+        .peer_manager
+        .lock()
+        .await
+        .peers()
+        .get(peer_id)
+        .map(|info| info.addrs.clone());
+    let Some(addrs) = addrs else {
+        return;
+    };
+
+    let ips: Vec<std::net::IpAddr> = addrs
+        .iter()
+        .filter_map(|addr| ip_blacklist::extract_ip_from_multiaddr(addr))
+        .collect();
+    if ips.is_empty() {
+        return;
+    }
+
+    {
+        let mut blacklist = state.ip_blacklist.lock().await;
+        for ip in &ips {
+            blacklist.escalate(*ip, until_ms);
+        }
+    }
+
+    let peer_store = state.peer_store.clone();
+    tokio::spawn(async move {
+        for ip in ips {
+            let record = peer_store::IpBanRecord {
+                ip: ip.to_string(),
+                banned_until_ms: until_ms,
+            };
+            if let Err(err) = peer_store.upsert_ip_ban(&record).await {
+                tracing::warn!(%err, "failed to persist auto-escalated IP ban to peer store");
+            }
+        }
+    });
+}
+
+/// Write a peer's connection metadata through to `peer_store` in the
+/// background, on connection open/close.
+fn persist_peer_metadata(state: &SharedState, info: &PeerInfo) {
+    let peer_store = state.peer_store.clone();
+    let record = peer_store::PeerMetadataRecord {
+        peer_id: info.peer_id.clone(),
+        first_seen_ms: info.connected_at,
+        last_seen_ms: info.last_seen_at,
+        addrs: info.addrs.clone(),
+        verified: info.verified,
+        handshake_failures: info.handshake_failures,
+    };
+    tokio::spawn(async move {
+        if let Err(err) = peer_store.upsert_peer_metadata(&record).await {
+            tracing::warn!(%err, "failed to persist peer metadata to peer store");
+        }
+    });
+}
+
+/// A `WorkRequest` dispatched directly to a ranked peer, and the remaining
+/// fallback candidates to try if that peer times out or fails.
+struct PendingDispatch {
+    request: WorkRequest,
+    remaining_candidates: Vec<dispatch::Candidate>,
 }
 
 #[derive(Debug, Serialize)]
@@ -403,6 +821,8 @@ struct LatencyHistogram {
     /// are stored in an overflow bucket.
     bucket_bounds_ms: [u64; 12],
     bucket_counts: [AtomicU64; 13],
+    /// Running sum of observed latencies, for a Prometheus-style `_sum`.
+    sum_ms: AtomicU64,
 }
 
 impl LatencyHistogram {
@@ -410,10 +830,12 @@ impl LatencyHistogram {
         Self {
             bucket_bounds_ms: [5, 10, 25, 50, 100, 150, 200, 300, 500, 1000, 2000, 5000],
             bucket_counts: std::array::from_fn(|_| AtomicU64::new(0)),
+            sum_ms: AtomicU64::new(0),
         }
     }
 
     fn observe(&self, latency_ms: u64) {
+        self.sum_ms.fetch_add(latency_ms, Ordering::Relaxed);
         for (idx, bound) in self.bucket_bounds_ms.iter().enumerate() {
             if latency_ms <= *bound {
                 self.bucket_counts[idx].fetch_add(1, Ordering::Relaxed);
@@ -423,6 +845,32 @@ impl LatencyHistogram {
         self.bucket_counts[self.bucket_counts.len() - 1].fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Cumulative `(le_bound, count)` pairs in Prometheus histogram order,
+    /// plus the overflow bucket exposed as `+Inf`.
+    fn cumulative_buckets(&self) -> Vec<(String, u64)> {
+        let mut running = 0u64;
+        let mut buckets: Vec<(String, u64)> = self
+            .bucket_bounds_ms
+            .iter()
+            .enumerate()
+            .map(|(idx, bound)| {
+                running += self.bucket_counts[idx].load(Ordering::Relaxed);
+                (bound.to_string(), running)
+            })
+            .collect();
+        running += self.bucket_counts[self.bucket_counts.len() - 1].load(Ordering::Relaxed);
+        buckets.push(("+Inf".to_string(), running));
+        buckets
+    }
+
+    fn sum_ms(&self) -> u64 {
+        self.sum_ms.load(Ordering::Relaxed)
+    }
+
+    fn total_count(&self) -> u64 {
+        self.bucket_counts.iter().map(|c| c.load(Ordering::Relaxed)).sum()
+    }
+
     fn percentiles(&self) -> LatencyPercentiles {
         let counts: Vec<u64> = self
             .bucket_counts
@@ -473,20 +921,96 @@ impl LatencyHistogram {
     }
 }
 
+/// Cumulative and 1-second-windowed inbound/outbound byte counters for the
+/// composed transport, fed by `libp2p::bandwidth::BandwidthSinks` and
+/// sampled on `bandwidth_tick`. Gives the auction/scheduling logic (and
+/// operators, via `/bandwidth`) a real congestion signal instead of the
+/// placeholder `load`/`capacity` fields alone.
+struct BandwidthMeter {
+    sinks: Arc<libp2p::bandwidth::BandwidthSinks>,
+    last_inbound: AtomicU64,
+    last_outbound: AtomicU64,
+    last_sampled_at_ms: AtomicU64,
+    inbound_bytes_per_sec: AtomicU64,
+    outbound_bytes_per_sec: AtomicU64,
+}
+
+impl BandwidthMeter {
+    fn new(sinks: Arc<libp2p::bandwidth::BandwidthSinks>) -> Self {
+        Self {
+            sinks,
+            last_inbound: AtomicU64::new(0),
+            last_outbound: AtomicU64::new(0),
+            last_sampled_at_ms: AtomicU64::new(now_ms() as u64),
+            inbound_bytes_per_sec: AtomicU64::new(0),
+            outbound_bytes_per_sec: AtomicU64::new(0),
+        }
+    }
+
+    /// Recompute the windowed byte rate from the cumulative counters
+    /// `BandwidthSinks` tracks internally.
+    fn sample(&self) {
+        let now = now_ms() as u64;
+        let inbound = self.sinks.total_inbound();
+        let outbound = self.sinks.total_outbound();
+        let last_at = self.last_sampled_at_ms.swap(now, Ordering::Relaxed);
+        let elapsed_secs = now.saturating_sub(last_at).max(1) as f64 / 1000.0;
+
+        let last_inbound = self.last_inbound.swap(inbound, Ordering::Relaxed);
+        let last_outbound = self.last_outbound.swap(outbound, Ordering::Relaxed);
+
+        self.inbound_bytes_per_sec.store(
+            (inbound.saturating_sub(last_inbound) as f64 / elapsed_secs) as u64,
+            Ordering::Relaxed,
+        );
+        self.outbound_bytes_per_sec.store(
+            (outbound.saturating_sub(last_outbound) as f64 / elapsed_secs) as u64,
+            Ordering::Relaxed,
+        );
+    }
+
+    fn stats(&self) -> BandwidthStats {
+        BandwidthStats {
+            inbound_bytes_total: self.sinks.total_inbound(),
+            outbound_bytes_total: self.sinks.total_outbound(),
+            inbound_bytes_per_sec: self.inbound_bytes_per_sec.load(Ordering::Relaxed),
+            outbound_bytes_per_sec: self.outbound_bytes_per_sec.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BandwidthStats {
+    inbound_bytes_total: u64,
+    outbound_bytes_total: u64,
+    inbound_bytes_per_sec: u64,
+    outbound_bytes_per_sec: u64,
+}
+
 // ─── libp2p Behaviour ───────────────────────────────────────────────────────
 
 #[derive(NetworkBehaviour)]
 struct ShardBehaviour {
     gossipsub: gossipsub::Behaviour,
     kad: KadBehaviour<MemoryStore>,
-    handshake: request_response::cbor::Behaviour<Heartbeat, Heartbeat>,
+    handshake: request_response::cbor::Behaviour<HandshakeMessage, HandshakeMessage>,
     verify: request_response::cbor::Behaviour<DraftSubmission, String>,
     control_work: request_response::cbor::Behaviour<WorkRequest, String>,
+    sampling: request_response::cbor::Behaviour<
+        peer_sampling::SamplingPushPull,
+        peer_sampling::SamplingPushPull,
+    >,
+    block_transfer: request_response::cbor::Behaviour<
+        block_transfer::ChunkRequest,
+        block_transfer::ChunkResponse,
+    >,
     relay_server: relay::Behaviour,
+    relay_client: relay::client::Behaviour,
     dcutr: dcutr::Behaviour,
     autonat: autonat::v1::Behaviour,
     identify: identify::Behaviour,
     ping: ping::Behaviour,
+    connection_limits: connection_limits::Behaviour,
 }
 
 // ─── Helpers ────────────────────────────────────────────────────────────────
@@ -503,6 +1027,75 @@ fn data_dir() -> std::path::PathBuf {
         .join("shard")
 }
 
+/// Gossip payloads older than this are propagation-stale: still well-formed,
+/// but not worth re-broadcasting or acting on.
+const STALE_GOSSIP_MS: u128 = 30_000;
+
+/// Bounds how many recent gossipsub message ids we remember for duplicate
+/// detection before evicting the oldest.
+const MAX_SEEN_GOSSIP_IDS: usize = 4096;
+
+/// Records a gossipsub message id as seen, returning `true` if it was
+/// already present (a duplicate that should be `Ignore`d rather than
+/// re-validated).
+fn remember_gossip_id(
+    seen_order: &mut VecDeque<gossipsub::MessageId>,
+    seen_set: &mut HashSet<gossipsub::MessageId>,
+    id: gossipsub::MessageId,
+) -> bool {
+    if !seen_set.insert(id.clone()) {
+        return true;
+    }
+    seen_order.push_back(id);
+    if seen_order.len() > MAX_SEEN_GOSSIP_IDS {
+        if let Some(oldest) = seen_order.pop_front() {
+            seen_set.remove(&oldest);
+        }
+    }
+    false
+}
+
+/// Per-topic gossipsub scoring weights shared by `shard-work-result`,
+/// `shard-forward-pass`, and `shard-backward-pass`: rewards time spent in the
+/// mesh and first-message-deliveries, and applies a negative weight to
+/// invalid-message deliveries (the `Reject` outcomes from explicit
+/// validation) so repeatedly-invalid publishers get mesh-pruned.
+fn gossipsub_topic_score_params() -> gossipsub::TopicScoreParams {
+    gossipsub::TopicScoreParams {
+        topic_weight: 1.0,
+        time_in_mesh_weight: 0.01,
+        time_in_mesh_quantum: Duration::from_secs(1),
+        time_in_mesh_cap: 3600.0,
+        first_message_deliveries_weight: 1.0,
+        first_message_deliveries_decay: 0.5,
+        first_message_deliveries_cap: 2000.0,
+        mesh_message_deliveries_weight: 0.0,
+        mesh_message_deliveries_decay: 0.5,
+        mesh_message_deliveries_cap: 100.0,
+        mesh_message_deliveries_threshold: 20.0,
+        mesh_message_deliveries_window: Duration::from_secs(10),
+        mesh_message_deliveries_activation: Duration::from_secs(5),
+        mesh_failure_penalty_weight: 0.0,
+        mesh_failure_penalty_decay: 0.5,
+        invalid_message_deliveries_weight: -20.0,
+        invalid_message_deliveries_decay: 0.3,
+    }
+}
+
+/// Builds the gossipsub `PeerScoreParams`, assigning [`gossipsub_topic_score_params`]
+/// to every topic this daemon scores.
+fn build_gossipsub_peer_score_params() -> gossipsub::PeerScoreParams {
+    let topic_params = gossipsub_topic_score_params();
+    let mut params = gossipsub::PeerScoreParams {
+        app_specific_weight: 1.0,
+        ..Default::default()
+    };
+    for topic in ["shard-work-result", "shard-forward-pass", "shard-backward-pass"] {
+        params.topics.insert(IdentTopic::new(topic).hash(), topic_params.clone());
+    }
+    params
+}
+
 fn unique_addrs(addrs: Vec<String>) -> Vec<String> {
     let mut seen = HashSet::new();
     let mut out = Vec::new();
@@ -514,6 +1107,60 @@ fn unique_addrs(addrs: Vec<String>) -> Vec<String> {
     out
 }
 
+/// How many addresses a `GetPeers` response returns at most.
+const GET_PEERS_SAMPLE_LIMIT: usize = 16;
+
+/// Coarse IP bucket for an address string, so [`sample_diverse_peers`] can
+/// spread its sample across subnets instead of favoring whichever subnet
+/// happens to dominate `known`: the first two octets of an embedded IPv4
+/// address, the full address for IPv6/anything else.
+fn addr_ip_bucket(addr: &str) -> String {
+    match ip_blacklist::extract_ip_from_multiaddr(addr) {
+        Some(std::net::IpAddr::V4(v4)) => {
+            let octets = v4.octets();
+            format!("{}.{}", octets[0], octets[1])
+        }
+        Some(ip) => ip.to_string(),
+        None => addr.to_string(),
+    }
+}
+
+/// Build a `GetPeers` response: a capped random sample of `known`, excluding
+/// anything in `exclude`, biased toward IP diversity by round-robining
+/// across subnet buckets rather than drawing uniformly (a single subnet
+/// flooding `known` shouldn't be able to fill the whole sample).
+fn sample_diverse_peers(known: &[String], exclude: &HashSet<String>, limit: usize) -> Vec<String> {
+    let mut by_bucket: HashMap<String, Vec<String>> = HashMap::new();
+    for addr in known.iter().filter(|addr| !exclude.contains(*addr)) {
+        by_bucket.entry(addr_ip_bucket(addr)).or_default().push(addr.clone());
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut buckets: Vec<Vec<String>> = by_bucket.into_values().collect();
+    for bucket in &mut buckets {
+        bucket.shuffle(&mut rng);
+    }
+    buckets.shuffle(&mut rng);
+
+    let mut sampled = Vec::new();
+    'fill: loop {
+        let mut added_any = false;
+        for bucket in &mut buckets {
+            if sampled.len() >= limit {
+                break 'fill;
+            }
+            if let Some(addr) = bucket.pop() {
+                sampled.push(addr);
+                added_any = true;
+            }
+        }
+        if !added_any {
+            break;
+        }
+    }
+    sampled
+}
+
 async fn read_bootstrap_file(path: &str) -> Vec<String> {
     let Ok(contents) = tokio::fs::read_to_string(path).await else {
         return Vec::new();
@@ -562,12 +1209,76 @@ fn validate_work_request(req: &WorkRequest) -> Result<(), String> {
     Ok(())
 }
 
+/// zstd level for `shard-work` gossip payloads: `prompt_context` can run up
+/// to 16KB and gets re-sent to every subscribed scout, so a cheap level that
+/// still meaningfully shrinks repetitive prompt text is worth the CPU.
+const WORK_REQUEST_COMPRESSION_LEVEL: i32 = 3;
+
+fn compress_work_request(req: &WorkRequest) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec(req).map_err(|e| format!("serializing WorkRequest: {e}"))?;
+    zstd::encode_all(json.as_slice(), WORK_REQUEST_COMPRESSION_LEVEL)
+        .map_err(|e| format!("compressing WorkRequest: {e}"))
+}
+
+fn decompress_work_request(bytes: &[u8]) -> Result<WorkRequest, String> {
+    let json = zstd::decode_all(bytes).map_err(|e| format!("decompressing WorkRequest: {e}"))?;
+    serde_json::from_slice(&json).map_err(|e| format!("deserializing WorkRequest: {e}"))
+}
+
+/// Register/advance a tensor transfer against an incoming gossip packet's
+/// `TensorChunkRef`, ingesting the chunk we were just sent and requesting
+/// whatever indices are still missing directly from the packet's source
+/// peer over the block-transfer protocol.
+#[allow(clippy::too_many_arguments)]
+async fn request_missing_chunks(
+    state: &SharedState,
+    swarm: &mut libp2p::Swarm<ShardBehaviour>,
+    pending_chunk_fetches: &mut HashMap<OutboundRequestId, block_transfer::ChunkRequest>,
+    request_id: &str,
+    step_id: &str,
+    source_peer_id: &str,
+    tensor_name: &str,
+    shape: Vec<usize>,
+    format: TensorDataFormat,
+    chunk: Option<&TensorChunkRef>,
+) {
+    let Some(chunk) = chunk else { return };
+    if chunk.total_chunks <= 1 {
+        return;
+    }
+
+    let mut transfers = state.transfers.lock().await;
+    transfers.start(request_id, step_id, tensor_name, shape, format, chunk.total_chunks);
+
+    if let Some(checksum) = &chunk.checksum_blake3 {
+        transfers.ingest_chunk(request_id, step_id, tensor_name, chunk.chunk_index, &chunk.data, checksum);
+    }
+
+    let missing = transfers.next_missing_chunks(request_id, step_id, tensor_name);
+    drop(transfers);
+
+    let Ok(source) = source_peer_id.parse::<PeerId>() else {
+        return;
+    };
+    for chunk_index in missing {
+        let req = block_transfer::ChunkRequest {
+            request_id: request_id.to_string(),
+            step_id: step_id.to_string(),
+            tensor_name: tensor_name.to_string(),
+            chunk_index,
+        };
+        let outbound_id = swarm.behaviour_mut().block_transfer.send_request(&source, req.clone());
+        pending_chunk_fetches.insert(outbound_id, req);
+    }
+}
+
 // ─── HTTP Control-Plane Handlers ────────────────────────────────────────────
 
 async fn health_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_json::Value> {
     let topo = state.topology.lock().await;
-    let peers = state.peers.lock().await;
-    let known = state.known_peers.lock().await;
+    let pm = state.peer_manager.lock().await;
+    let peers = pm.peers();
+    let known = pm.known();
     let verified_count = peers.values().filter(|p| p.verified).count();
     let capacity = state.capacity.load(Ordering::Relaxed);
     let load = state.current_load.load(Ordering::Relaxed);
@@ -593,7 +1304,8 @@ async fn health_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_
 
 async fn topology_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_json::Value> {
     let topo = state.topology.lock().await;
-    let known = state.known_peers.lock().await;
+    let pm = state.peer_manager.lock().await;
+    let known = pm.known();
     let capacity = state.capacity.load(Ordering::Relaxed);
     let load = state.current_load.load(Ordering::Relaxed);
     let latency_ms = state.avg_latency_ms.load(Ordering::Relaxed);
@@ -613,12 +1325,28 @@ async fn topology_handler(AxumState(state): AxumState<SharedState>) -> Json<serd
         "capacity": capacity,
         "load": load,
         "latency_ms": latency_ms,
+        "bandwidth": state.bandwidth.stats(),
     }))
 }
 
 async fn peers_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_json::Value> {
-    let peers = state.peers.lock().await;
-    let list: Vec<&PeerInfo> = peers.values().collect();
+    let pm = state.peer_manager.lock().await;
+    let peers = pm.peers();
+    let peering = state.peering.lock().await;
+    let list: Vec<serde_json::Value> = peers
+        .values()
+        .map(|peer| {
+            let mut record = serde_json::to_value(peer).unwrap_or(serde_json::Value::Null);
+            let rtt_ms = peer
+                .addrs
+                .first()
+                .and_then(|addr| peering.rtt_ms(addr));
+            if let serde_json::Value::Object(fields) = &mut record {
+                fields.insert("smoothed_rtt_ms".to_string(), serde_json::json!(rtt_ms));
+            }
+            record
+        })
+        .collect();
     Json(serde_json::json!({ "peers": list, "count": list.len() }))
 }
 
@@ -636,6 +1364,14 @@ async fn broadcast_work_handler(
     }
 }
 
+async fn pop_work_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_json::Value> {
+    let mut incoming = state.incoming_work.lock().await;
+    match incoming.pop_front() {
+        Some(req) => Json(serde_json::json!({ "request": req })),
+        None => Json(serde_json::json!({ "request": null })),
+    }
+}
+
 async fn pop_result_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_json::Value> {
     let mut results = state.results.lock().await;
     match results.pop_front() {
@@ -648,6 +1384,7 @@ async fn latency_profile_handler(
     AxumState(state): AxumState<SharedState>,
 ) -> Json<serde_json::Value> {
     let p = state.gossipsub_latency_hist.percentiles();
+    let wp = state.work_request_latency_hist.percentiles();
     Json(serde_json::json!({
         "source": "rust-sidecar",
         "gossipsub_propagation_ms": {
@@ -655,29 +1392,216 @@ async fn latency_profile_handler(
             "p90": p.p90_ms,
             "p99": p.p99_ms,
             "samples": p.samples,
+        },
+        "work_request_propagation_ms": {
+            "p50": wp.p50_ms,
+            "p90": wp.p90_ms,
+            "p99": wp.p99_ms,
+            "samples": wp.samples,
         }
     }))
 }
 
+/// Renders internal counters in Prometheus text exposition format: the
+/// gossipsub propagation histogram as a native `_bucket`/`_sum`/`_count`
+/// histogram over the existing `bucket_bounds_ms` edges, gauges for peer
+/// counts/capacity/load, and per-peer scout success-rate/blackholed gauges
+/// labeled by `peer_id`. Lets operators scrape this node into an existing
+/// Prometheus/Grafana stack instead of polling the JSON endpoints.
+async fn metrics_handler(AxumState(state): AxumState<SharedState>) -> impl IntoResponse {
+    let mut body = String::new();
+
+    let pm = state.peer_manager.lock().await;
+    let connected_peers = pm.peers().len();
+    let verified_peers = pm.peers().values().filter(|p| p.verified).count();
+    let known_peers = pm.known().len();
+    drop(pm);
+
+    body.push_str("# HELP shard_connected_peers Number of peers currently connected.\n");
+    body.push_str("# TYPE shard_connected_peers gauge\n");
+    body.push_str(&format!("shard_connected_peers {connected_peers}\n"));
+    body.push_str("# HELP shard_verified_peers Number of connected peers with a verified capability attestation.\n");
+    body.push_str("# TYPE shard_verified_peers gauge\n");
+    body.push_str(&format!("shard_verified_peers {verified_peers}\n"));
+    body.push_str("# HELP shard_known_peers Number of peer addresses known to this node.\n");
+    body.push_str("# TYPE shard_known_peers gauge\n");
+    body.push_str(&format!("shard_known_peers {known_peers}\n"));
+
+    body.push_str("# HELP shard_capacity_tokens_per_sec Advertised token throughput capacity of this node.\n");
+    body.push_str("# TYPE shard_capacity_tokens_per_sec gauge\n");
+    body.push_str(&format!(
+        "shard_capacity_tokens_per_sec {}\n",
+        state.capacity.load(Ordering::Relaxed)
+    ));
+    body.push_str("# HELP shard_current_load Current active request count on this node.\n");
+    body.push_str("# TYPE shard_current_load gauge\n");
+    body.push_str(&format!(
+        "shard_current_load {}\n",
+        state.current_load.load(Ordering::Relaxed)
+    ));
+
+    body.push_str("# HELP shard_gossipsub_propagation_ms Observed gossipsub message propagation latency.\n");
+    body.push_str("# TYPE shard_gossipsub_propagation_ms histogram\n");
+    for (le, count) in state.gossipsub_latency_hist.cumulative_buckets() {
+        body.push_str(&format!(
+            "shard_gossipsub_propagation_ms_bucket{{le=\"{le}\"}} {count}\n"
+        ));
+    }
+    body.push_str(&format!(
+        "shard_gossipsub_propagation_ms_sum {}\n",
+        state.gossipsub_latency_hist.sum_ms()
+    ));
+    body.push_str(&format!(
+        "shard_gossipsub_propagation_ms_count {}\n",
+        state.gossipsub_latency_hist.total_count()
+    ));
+
+    body.push_str("# HELP shard_work_request_propagation_ms Observed shard-work WorkRequest gossipsub propagation latency.\n");
+    body.push_str("# TYPE shard_work_request_propagation_ms histogram\n");
+    for (le, count) in state.work_request_latency_hist.cumulative_buckets() {
+        body.push_str(&format!(
+            "shard_work_request_propagation_ms_bucket{{le=\"{le}\"}} {count}\n"
+        ));
+    }
+    body.push_str(&format!(
+        "shard_work_request_propagation_ms_sum {}\n",
+        state.work_request_latency_hist.sum_ms()
+    ));
+    body.push_str(&format!(
+        "shard_work_request_propagation_ms_count {}\n",
+        state.work_request_latency_hist.total_count()
+    ));
+
+    body.push_str("# HELP shard_scout_success_rate Recent scout acceptance success rate, per peer.\n");
+    body.push_str("# TYPE shard_scout_success_rate gauge\n");
+    body.push_str("# HELP shard_scout_blackholed Whether a scout peer is currently blackholed (1) or not (0).\n");
+    body.push_str("# TYPE shard_scout_blackholed gauge\n");
+    let pm = state.peer_manager.lock().await;
+    for status in pm.penalties().all_statuses() {
+        body.push_str(&format!(
+            "shard_scout_success_rate{{peer_id=\"{}\"}} {}\n",
+            status.peer_id, status.success_rate
+        ));
+        body.push_str(&format!(
+            "shard_scout_blackholed{{peer_id=\"{}\"}} {}\n",
+            status.peer_id,
+            status.blackholed as u8
+        ));
+    }
+    drop(pm);
+
+    ([("content-type", "text/plain; version=0.0.4")], body)
+}
+
 async fn scout_penalty_update_handler(
     AxumState(state): AxumState<SharedState>,
     Json(update): Json<ScoutPenaltyUpdate>,
 ) -> Json<serde_json::Value> {
-    let mut penalties = state.scout_penalties.lock().await;
-    let status = penalties.apply_update(update);
+    let mut pm = state.peer_manager.lock().await;
+    let status = pm.report_peer(update);
+    drop(pm);
+    persist_scout_status(&state, &status).await;
     Json(serde_json::json!({"ok": true, "status": status}))
 }
 
 async fn scout_penalty_status_handler(
     AxumState(state): AxumState<SharedState>,
 ) -> Json<serde_json::Value> {
-    let penalties = state.scout_penalties.lock().await;
+    let pm = state.peer_manager.lock().await;
     Json(serde_json::json!({
         "ok": true,
-        "peers": penalties.all_statuses(),
+        "peers": pm.penalties().all_statuses(),
+    }))
+}
+
+async fn transfers_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_json::Value> {
+    let transfers = state.transfers.lock().await;
+    let statuses = transfers.statuses();
+    Json(serde_json::json!({ "transfers": statuses, "count": statuses.len() }))
+}
+
+async fn sampling_view_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_json::Value> {
+    let view = state.sampling_view.lock().await;
+    let sample = view.sample();
+    Json(serde_json::json!({ "view": sample, "size": sample.len() }))
+}
+
+async fn bandwidth_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!(state.bandwidth.stats()))
+}
+
+async fn connections_handler(AxumState(state): AxumState<SharedState>) -> Json<serde_json::Value> {
+    let pm = state.peer_manager.lock().await;
+    let peers = pm.peers();
+    let inbound = peers.values().filter(|p| p.inbound).count();
+    let outbound = peers.len() - inbound;
+    let budget = state.connection_budget.lock().await;
+    Json(serde_json::json!({
+        "limits": {
+            "max_total": budget.max_total,
+            "max_pending_incoming": budget.max_pending_incoming,
+            "max_per_peer": budget.max_per_peer,
+            "excess_factor": budget.excess_factor,
+            "inbound_budget": budget.inbound_budget(),
+            "reserved_peers": budget.reserved_peers,
+            "deny_unreserved": budget.deny_unreserved,
+        },
+        "counts": {
+            "total": peers.len(),
+            "inbound": inbound,
+            "outbound": outbound,
+        },
     }))
 }
 
+#[derive(Debug, Deserialize)]
+struct AddReservedPeerRequest {
+    /// A full `Multiaddr` (preferred, so the reconnect loop can dial it) or
+    /// a bare `PeerId` string.
+    addr: String,
+}
+
+async fn add_reserved_peer_handler(
+    AxumState(state): AxumState<SharedState>,
+    Json(req): Json<AddReservedPeerRequest>,
+) -> Json<serde_json::Value> {
+    state
+        .connection_budget
+        .lock()
+        .await
+        .add_reserved_peer(req.addr.clone());
+    Json(serde_json::json!({"ok": true, "reserved": req.addr}))
+}
+
+async fn remove_reserved_peer_handler(
+    AxumState(state): AxumState<SharedState>,
+    AxumPath(peer_id): AxumPath<String>,
+) -> Json<serde_json::Value> {
+    let removed = state
+        .connection_budget
+        .lock()
+        .await
+        .remove_reserved_peer(&peer_id);
+    Json(serde_json::json!({"ok": true, "removed": removed}))
+}
+
+#[derive(Debug, Deserialize)]
+struct DenyUnreservedRequest {
+    enabled: bool,
+}
+
+async fn deny_unreserved_peers_handler(
+    AxumState(state): AxumState<SharedState>,
+    Json(req): Json<DenyUnreservedRequest>,
+) -> Json<serde_json::Value> {
+    state
+        .connection_budget
+        .lock()
+        .await
+        .set_deny_unreserved(req.enabled);
+    Json(serde_json::json!({"ok": true, "deny_unreserved": req.enabled}))
+}
+
 fn create_router(state: SharedState) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -690,9 +1614,21 @@ fn create_router(state: SharedState) -> Router {
         .route("/peers", get(peers_handler))
         .route("/broadcast-work", post(broadcast_work_handler))
         .route("/pop-result", get(pop_result_handler))
+        .route("/pop-work", get(pop_work_handler))
         .route("/scout/penalty", post(scout_penalty_update_handler))
         .route("/scout/penalty", get(scout_penalty_status_handler))
+        .route("/metrics", get(metrics_handler))
         .route("/metrics/latency-profile", get(latency_profile_handler))
+        .route("/sampling/view", get(sampling_view_handler))
+        .route("/transfers", get(transfers_handler))
+        .route("/bandwidth", get(bandwidth_handler))
+        .route("/connections", get(connections_handler))
+        .route("/peers/reserved", post(add_reserved_peer_handler))
+        .route("/peers/reserved/:peer_id", delete(remove_reserved_peer_handler))
+        .route(
+            "/peers/reserved/deny-unreserved",
+            post(deny_unreserved_peers_handler),
+        )
         .layer(cors)
         .with_state(state)
 }
@@ -738,35 +1674,6 @@ async fn main() -> Result<()> {
     let id_keys = identity::Keypair::generate_ed25519();
     let local_peer_id = PeerId::from(id_keys.public());
 
-    let state = SharedState {
-        topology: Arc::new(Mutex::new(TopologyState {
-            local_peer_id: local_peer_id.to_string(),
-            listen_addrs: Vec::new(),
-            webrtc_addr: None,
-            quic_addr: None,
-            ws_addr: None,
-            public_api_addr: cli.public_host.clone(),
-            is_public: cli.public_api,
-            relay_server_enabled: cli.relay_server,
-            contribute_enabled: cli.contribute,
-            capacity: 100, // Default: 100 tokens/sec
-            load: 0,
-            latency_ms: 0.0,
-        })),
-        peers: Arc::new(Mutex::new(HashMap::new())),
-        known_peers: Arc::new(Mutex::new(bootstrap_addrs.clone())),
-        results: Arc::new(Mutex::new(VecDeque::new())),
-        work_tx,
-        daemon_start: now_ms(),
-        capacity: Arc::new(AtomicU32::new(100)), // Default: 100 tokens/sec
-        current_load: Arc::new(AtomicU32::new(0)),
-        avg_latency_ms: Arc::new(AtomicU32::new(0)),
-        gossipsub_latency_hist: Arc::new(LatencyHistogram::new()),
-        scout_penalties: Arc::new(Mutex::new(ScoutPenaltyBook::default())),
-        backward_passes: Arc::new(Mutex::new(VecDeque::new())),
-    };
-
-    // ── build swarm ──
     // ── build transport ──
     let tcp_config = libp2p::tcp::Config::default().nodelay(true);
     let dns_tcp = libp2p::dns::tokio::Transport::system(libp2p::tcp::tokio::Transport::new(
@@ -778,8 +1685,21 @@ async fn main() -> Result<()> {
 
     let tcp_ws = libp2p::core::transport::OrTransport::new(dns_tcp, ws_dns_tcp);
 
-    let authenticated_transport = tcp_ws
-        .upgrade(libp2p::core::upgrade::Version::V1)
+    // The relay client transport dials/listens over `/p2p-circuit` addresses
+    // relayed through another node's `relay::Behaviour` server; folding it
+    // into the same OrTransport as tcp/ws means it gets the same
+    // noise+yamux upgrade below instead of needing its own security stack.
+    let (relay_transport, relay_client) = relay::client::new(local_peer_id);
+    let tcp_ws_relay = libp2p::core::transport::OrTransport::new(relay_transport, tcp_ws);
+
+    // `V1Lazy` negotiates the simultaneous-open extension: when both peers
+    // dial each other at once during a DCUtR hole punch, each side sends a
+    // random nonce and the higher nonce becomes the multistream-select
+    // initiator (ties are retried) instead of both sides deadlocking as
+    // dialers. Plain `V1` doesn't support this and hole punches never
+    // complete the upgrade.
+    let authenticated_transport = tcp_ws_relay
+        .upgrade(libp2p::core::upgrade::Version::V1Lazy)
         .authenticate(libp2p::noise::Config::new(&id_keys).expect("Noise config failed"))
         .multiplex(libp2p::yamux::Config::default());
 
@@ -806,13 +1726,151 @@ async fn main() -> Result<()> {
         })
         .boxed();
 
+    // Wrap the fully-composed transport in a bandwidth-logging layer so the
+    // daemon can report real inbound/outbound byte rates instead of the
+    // placeholder `load`/`capacity` fields alone — this has to happen after
+    // the final `.boxed()` so it counts bytes on every transport (TCP, WS,
+    // WebRTC, QUIC) uniformly rather than per-branch.
+    let (transport, bandwidth_sinks) = libp2p::bandwidth::BandwidthLogging::new(transport);
+    let transport = transport.boxed();
+    let bandwidth = Arc::new(BandwidthMeter::new(bandwidth_sinks));
+
+    let connection_budget = Arc::new(Mutex::new(connection_budget::ConnectionBudget {
+        max_total: cli.max_connections,
+        max_pending_incoming: cli.max_pending_incoming,
+        max_per_peer: cli.max_connections_per_peer,
+        excess_factor: cli.connection_excess_factor,
+        reserved_peers: cli.reserved_peer.clone(),
+        deny_unreserved: false,
+    }));
+
+    // ── rehydrate peer reputation/metadata from the last run, so a scout
+    // we'd blackholed doesn't come back clean after a restart ──
+    let peer_store = Arc::new(peer_store::PeerStore::open(&data.join("peers.db")).await?);
+
+    let mut scout_penalties = ScoutPenaltyBook::default();
+    for record in peer_store.load_all_penalties().await.unwrap_or_default() {
+        scout_penalties.rehydrate(&record);
+    }
+
+    // Rehydrated entries aren't actually connected yet — `inbound: false`
+    // keeps them out of peer-excess eviction's inbound count until a real
+    // `ConnectionEstablished` overwrites them.
+    let mut rehydrated_peers = HashMap::new();
+    for record in peer_store.load_all_peer_metadata().await.unwrap_or_default() {
+        rehydrated_peers.insert(
+            record.peer_id.clone(),
+            PeerInfo {
+                peer_id: record.peer_id,
+                connected_at: record.first_seen_ms,
+                last_seen_at: record.last_seen_ms,
+                addrs: record.addrs,
+                verified: record.verified,
+                handshake_failures: record.handshake_failures,
+                reported_capacity: None,
+                reported_load: None,
+                score: None,
+                hole_punch_succeeded: None,
+                inbound: false,
+            },
+        );
+    }
+
+    // ── IP/CIDR blacklist: config entries plus rehydrated auto-escalations
+    // from the peer store, so a re-keyed scout stays blocked across a
+    // restart the same way a banned peer_id does ──
+    let static_ip_entries: Vec<ip_blacklist::CidrEntry> = cli
+        .ip_blacklist
+        .iter()
+        .filter_map(|raw| {
+            let parsed = ip_blacklist::parse_entry(raw);
+            if parsed.is_none() {
+                tracing::warn!(entry = %raw, "skipping unparseable --ip-blacklist entry");
+            }
+            parsed
+        })
+        .collect();
+    let mut ip_blacklist = ip_blacklist::IpBlacklist::new(static_ip_entries);
+    for record in peer_store.load_all_ip_bans().await.unwrap_or_default() {
+        if let Ok(ip) = record.ip.parse() {
+            ip_blacklist.escalate(ip, record.banned_until_ms);
+        }
+    }
+
+    let state = SharedState {
+        topology: Arc::new(Mutex::new(TopologyState {
+            local_peer_id: local_peer_id.to_string(),
+            listen_addrs: Vec::new(),
+            webrtc_addr: None,
+            quic_addr: None,
+            ws_addr: None,
+            public_api_addr: cli.public_host.clone(),
+            is_public: cli.public_api,
+            relay_server_enabled: cli.relay_server,
+            contribute_enabled: cli.contribute,
+            capacity: 100, // Default: 100 tokens/sec
+            load: 0,
+            latency_ms: 0.0,
+        })),
+        peer_manager: Arc::new(Mutex::new(peer_manager::PeerManager::new(
+            rehydrated_peers,
+            bootstrap_addrs.clone(),
+            scout_penalties,
+        ))),
+        results: Arc::new(Mutex::new(VecDeque::new())),
+        incoming_work: Arc::new(Mutex::new(VecDeque::new())),
+        work_tx,
+        daemon_start: now_ms(),
+        capacity: Arc::new(AtomicU32::new(100)), // Default: 100 tokens/sec
+        current_load: Arc::new(AtomicU32::new(0)),
+        avg_latency_ms: Arc::new(AtomicU32::new(0)),
+        gossipsub_latency_hist: Arc::new(LatencyHistogram::new()),
+        work_request_latency_hist: Arc::new(LatencyHistogram::new()),
+        backward_passes: Arc::new(Mutex::new(VecDeque::new())),
+        telemetry_history: telemetry_ws::TelemetryHistory::new(),
+        sampling_view: Arc::new(Mutex::new(peer_sampling::SamplingView::new())),
+        peering: Arc::new(Mutex::new(peering::PeeringManager::new())),
+        transfers: Arc::new(Mutex::new(block_transfer::TransferManager::new())),
+        bandwidth: bandwidth.clone(),
+        connection_budget: connection_budget.clone(),
+        peer_store: peer_store.clone(),
+        ip_blacklist: Arc::new(Mutex::new(ip_blacklist)),
+    };
+
     // ── build swarm ──
     let behaviour = {
-        let gossipsub = gossipsub::Behaviour::new(
+        // `validate_messages()` + `ValidationMode::Strict` hold incoming
+        // messages as "unvalidated" until we explicitly report an
+        // acceptance decision, so a peer flooding malformed payloads
+        // doesn't get its traffic amplified across the mesh before we've
+        // even deserialized it.
+        let gossipsub_config = gossipsub::ConfigBuilder::default()
+            .validation_mode(gossipsub::ValidationMode::Strict)
+            .validate_messages()
+            .build()
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let mut gossipsub = gossipsub::Behaviour::new(
             MessageAuthenticity::Signed(id_keys.clone()),
-            gossipsub::Config::default(),
+            gossipsub_config,
         )
         .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        // Peer scoring complements the explicit Accept/Reject validation
+        // above: validation catches a single bad message, scoring catches a
+        // peer that's bad *on average* (floods graylist-worthy messages,
+        // never first-delivers anything) and demotes/cuts them out of the
+        // mesh before `scout_penalties` blackholes them outright.
+        gossipsub
+            .with_peer_score(
+                build_gossipsub_peer_score_params(),
+                gossipsub::PeerScoreThresholds {
+                    gossip_threshold: cli.gossipsub_score_gossip_threshold,
+                    publish_threshold: cli.gossipsub_score_publish_threshold,
+                    graylist_threshold: cli.gossipsub_score_graylist_threshold,
+                    accept_px_threshold: cli.gossipsub_score_accept_px_threshold,
+                    opportunistic_graft_threshold: 5.0,
+                },
+            )
+            .map_err(|e| anyhow::anyhow!(e))?;
         let kad = KadBehaviour::new(local_peer_id, MemoryStore::new(local_peer_id));
         let handshake = request_response::cbor::Behaviour::new(
             [(
@@ -835,25 +1893,56 @@ async fn main() -> Result<()> {
             )],
             request_response::Config::default(),
         );
-        let relay_server = relay::Behaviour::new(local_peer_id, Default::default());
-        let dcutr = dcutr::Behaviour::new(local_peer_id);
+        let sampling = request_response::cbor::Behaviour::new(
+            [(
+                StreamProtocol::new("/shard/1.0.0/sampling"),
+                ProtocolSupport::Full,
+            )],
+            request_response::Config::default(),
+        );
+        let block_transfer = request_response::cbor::Behaviour::new(
+            [(
+                StreamProtocol::new("/shard/1.0.0/block-transfer"),
+                ProtocolSupport::Full,
+            )],
+            request_response::Config::default(),
+        );
+        let relay_server = relay::Behaviour::new(local_peer_id, Default::default());
+        let dcutr = dcutr::Behaviour::new(local_peer_id);
         let autonat = autonat::v1::Behaviour::new(local_peer_id, autonat::v1::Config::default());
         let identify = identify::Behaviour::new(identify::Config::new(
             "/shard/1.0.0".to_string(),
             id_keys.public(),
         ));
         let ping = ping::Behaviour::new(ping::Config::new());
+        // Hard ceilings enforced before a connection is allowed to finish
+        // establishing, ahead of `should_reject_peer_connection`'s
+        // post-establishment blackhole check. The excess-factor-based
+        // eviction of low-value inbound peers is handled separately in the
+        // event loop via `connection_budget::lowest_value_inbound_peer`,
+        // since ranking by score/reputation isn't something this behaviour
+        // knows how to do.
+        let connection_limits = connection_limits::Behaviour::new(
+            connection_limits::ConnectionLimits::default()
+                .with_max_established(Some(cli.max_connections))
+                .with_max_established_per_peer(Some(cli.max_connections_per_peer))
+                .with_max_pending_incoming(Some(cli.max_pending_incoming)),
+        );
         ShardBehaviour {
             gossipsub,
             kad,
             handshake,
             verify,
             control_work,
+            sampling,
+            block_transfer,
             relay_server,
+            relay_client,
             dcutr,
             autonat,
             identify,
             ping,
+            connection_limits,
         }
     };
 
@@ -895,7 +1984,20 @@ async fn main() -> Result<()> {
         }
     }
 
-    telemetry_ws::spawn_telemetry_ws_server(state.clone(), cli.telemetry_ws_port);
+    let telemetry_tls = match (&cli.telemetry_tls_cert, &cli.telemetry_tls_key) {
+        (Some(cert_path), Some(key_path)) => Some(telemetry_ws::TelemetryTlsConfig {
+            cert_path: cert_path.clone(),
+            key_path: key_path.clone(),
+        }),
+        (None, None) => None,
+        _ => {
+            tracing::warn!(
+                "telemetry_tls_cert and telemetry_tls_key must both be set; falling back to plaintext"
+            );
+            None
+        }
+    };
+    telemetry_ws::spawn_telemetry_ws_server(state.clone(), cli.telemetry_ws_port, telemetry_tls);
 
     // ── spawn HTTP control-plane server ──
     let http_state = state.clone();
@@ -979,42 +2081,174 @@ async fn main() -> Result<()> {
     println!();
 
     let mut reconnect_tick = tokio::time::interval(Duration::from_secs(cli.reconnect_seconds));
+    let mut sampling_tick = tokio::time::interval(Duration::from_secs(5));
+    let mut score_tick = tokio::time::interval(Duration::from_secs(10));
+    let mut discovery_tick = tokio::time::interval(Duration::from_secs(30));
+    let mut bandwidth_tick = tokio::time::interval(Duration::from_secs(1));
     let mut pending_handshakes: HashMap<OutboundRequestId, PeerId> = HashMap::new();
+    let mut pending_dispatches: HashMap<OutboundRequestId, PendingDispatch> = HashMap::new();
+    let mut pending_chunk_fetches: HashMap<OutboundRequestId, block_transfer::ChunkRequest> =
+        HashMap::new();
+    let mut seen_gossip_order: VecDeque<gossipsub::MessageId> = VecDeque::new();
+    let mut seen_gossip_ids: HashSet<gossipsub::MessageId> = HashSet::new();
 
     // ── main event loop ──
     loop {
         tokio::select! {
             _ = reconnect_tick.tick() => {
-                let known = state.known_peers.lock().await.clone();
-                let connected: HashSet<String> = state.peers.lock().await.keys().cloned().collect();
-                for addr_str in known {
+                // Reserved peers are maintained even if they fell out of
+                // (or never made it into) `known_peers`, so a coordinator
+                // can pin a stable backbone independent of churn.
+                let mut known = state.peer_manager.lock().await.known().to_vec();
+                known.extend(state.connection_budget.lock().await.reserved_addrs());
+                let known = unique_addrs(known);
+                let due = state.peering.lock().await.due_for_reconnect(&known);
+                for addr_str in due {
                     if let Ok(addr) = addr_str.parse::<Multiaddr>() {
                         let is_self = addr.to_string().contains(&local_peer_id.to_string());
-                        if !is_self {
-                            // Attempt periodic redial for resilience.
-                            if let Err(err) = swarm.dial(addr.clone()) {
-                                tracing::debug!(%addr, %err, "reconnect dial skipped/failed");
-                            } else {
-                                tracing::debug!(%addr, connected = connected.len(), "reconnect dial attempted");
+                        if is_self {
+                            continue;
+                        }
+
+                        // Prefer a direct address over a relayed one: don't
+                        // re-dial a `/p2p-circuit` address for a peer we've
+                        // already DCUtR hole-punched through to directly.
+                        if addr_str.contains("/p2p-circuit") {
+                            let pm = state.peer_manager.lock().await;
+                            let already_direct = pm.peers().iter().any(|(peer_id, info)| {
+                                addr_str.contains(peer_id.as_str())
+                                    && info.hole_punch_succeeded == Some(true)
+                            });
+                            drop(pm);
+                            if already_direct {
+                                tracing::debug!(
+                                    %addr,
+                                    "skipping relayed reconnect, direct hole punch already succeeded"
+                                );
+                                continue;
                             }
                         }
+
+                        let mut peering = state.peering.lock().await;
+                        if let Err(err) = swarm.dial(addr.clone()) {
+                            tracing::debug!(%addr, %err, "reconnect dial skipped/failed");
+                            peering.mark_down(&addr_str);
+                        } else {
+                            tracing::debug!(%addr, "reconnect dial attempted");
+                            peering.mark_connecting(&addr_str);
+                        }
+                    }
+                }
+            }
+
+            // ── Basalt push/pull: refresh the sampled peer view ──
+            _ = sampling_tick.tick() => {
+                let mut view = state.sampling_view.lock().await;
+                view.maybe_redraw();
+                if let Some(partner) = view.random_peer() {
+                    if let Ok(partner_id) = partner.peer_id.parse::<PeerId>() {
+                        let push = peer_sampling::SamplingPushPull { view: view.sample() };
+                        drop(view);
+                        swarm.behaviour_mut().sampling.send_request(&partner_id, push);
+                    }
+                }
+            }
+
+            // ── active discovery: ask a random connected peer for a sample
+            // of its address book, so the node can bootstrap beyond its own
+            // seed list instead of only ever learning addresses from
+            // inbound connections ──
+            _ = discovery_tick.tick() => {
+                let peer_ids: Vec<String> = state.peer_manager.lock().await.peers().keys().cloned().collect();
+                if let Some(partner_id_str) = peer_ids.choose(&mut rand::thread_rng()) {
+                    if let Ok(partner_id) = partner_id_str.parse::<PeerId>() {
+                        let id = swarm
+                            .behaviour_mut()
+                            .handshake
+                            .send_request(&partner_id, HandshakeMessage::GetPeers);
+                        pending_handshakes.insert(id, partner_id);
+                    }
+                }
+            }
+
+            // ── resample the transport's windowed inbound/outbound byte rate ──
+            _ = bandwidth_tick.tick() => {
+                state.bandwidth.sample();
+            }
+
+            // ── refresh each connected peer's gossipsub score and feed
+            // scores below the graylist threshold into scout_penalties, so a
+            // peer that's quietly bad across many topics eventually gets
+            // blackholed the same way an explicitly-rejected peer would ──
+            _ = score_tick.tick() => {
+                let peer_ids: Vec<String> = state.peer_manager.lock().await.peers().keys().cloned().collect();
+                for peer_id_str in peer_ids {
+                    let Ok(peer_id) = peer_id_str.parse::<PeerId>() else {
+                        continue;
+                    };
+                    let score = swarm.behaviour().gossipsub.peer_score(&peer_id);
+                    if let Some(score) = score {
+                        let is_reserved = state.connection_budget.lock().await.is_reserved(&peer_id_str);
+                        let mut pm = state.peer_manager.lock().await;
+                        pm.record_score(&peer_id_str, score);
+                        let status = if score < cli.gossipsub_score_graylist_threshold && !is_reserved {
+                            Some(pm.report_peer(ScoutPenaltyUpdate {
+                                peer_id: peer_id_str,
+                                accepted: false,
+                                probability_bound: 1.0,
+                                reason: Some("gossipsub peer score below graylist threshold".into()),
+                            }))
+                        } else {
+                            None
+                        };
+                        drop(pm);
+                        if let Some(status) = status {
+                            persist_scout_status(&state, &status).await;
+                        }
                     }
                 }
             }
 
-            // ── inbound work from Python driver (HTTP → gossipsub) ──
+            // ── inbound work from Python driver: dispatch directly to the
+            // best-fit peer when capacity metadata is available, else
+            // broadcast to the shard-work gossipsub topic as before ──
             Some(mut work_req) = work_rx.recv() => {
                 if work_req.created_at_ms.is_none() {
                     work_req.created_at_ms = Some(now_ms());
                 }
-                match serde_json::to_vec(&work_req) {
+
+                let candidates = {
+                    let mut pm = state.peer_manager.lock().await;
+                    let peers = pm.peers().clone();
+                    let peering = state.peering.lock().await;
+                    dispatch::rank_candidates(
+                        &peers,
+                        pm.penalties_mut(),
+                        |addr| peering.rtt_ms(addr),
+                        work_req.min_tokens,
+                    )
+                };
+
+                if let Some((first, rest)) = candidates.split_first() {
+                    if let Ok(target) = first.peer_id.parse::<PeerId>() {
+                        tracing::info!(id = %work_req.request_id, peer = %first.peer_id, "dispatching WorkRequest directly");
+                        let id = swarm.behaviour_mut().control_work.send_request(&target, work_req.clone());
+                        pending_dispatches.insert(id, PendingDispatch {
+                            request: work_req,
+                            remaining_candidates: rest.to_vec(),
+                        });
+                        continue;
+                    }
+                }
+
+                match compress_work_request(&work_req) {
                     Ok(payload) => {
                         match swarm.behaviour_mut().gossipsub.publish(work_topic.clone(), payload) {
-                            Ok(_) => tracing::info!(id = %work_req.request_id, "published WorkRequest to gossipsub"),
+                            Ok(_) => tracing::info!(id = %work_req.request_id, "published WorkRequest to gossipsub (no capacity-ranked peer available)"),
                             Err(e) => tracing::warn!(id = %work_req.request_id, %e, "gossipsub publish failed (no peers?)"),
                         }
                     }
-                    Err(e) => tracing::error!(%e, "failed to serialize WorkRequest"),
+                    Err(e) => tracing::error!(%e, "failed to serialize/compress WorkRequest"),
                 }
             }
 
@@ -1022,126 +2256,553 @@ async fn main() -> Result<()> {
             event = swarm.select_next_some() => {
                 match event {
                     // ── gossipsub ──
-                    SwarmEvent::Behaviour(ShardBehaviourEvent::Gossipsub(gossipsub::Event::Message { message, .. })) => {
+                    SwarmEvent::Behaviour(ShardBehaviourEvent::Gossipsub(gossipsub::Event::Message {
+                        propagation_source,
+                        message_id,
+                        message,
+                    })) => {
+                        if remember_gossip_id(&mut seen_gossip_order, &mut seen_gossip_ids, message_id.clone()) {
+                            let _ = swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                                &message_id,
+                                &propagation_source,
+                                gossipsub::MessageAcceptance::Ignore,
+                            );
+                            continue;
+                        }
+
                         if message.topic == result_topic.hash() {
-                            if let Ok(result) = serde_json::from_slice::<WorkResponse>(&message.data) {
-                                let peer_is_blackholed = {
-                                    let mut penalties = state.scout_penalties.lock().await;
-                                    penalties.is_blackholed(&result.peer_id)
-                                };
-                                if peer_is_blackholed {
-                                    tracing::warn!(peer = %result.peer_id, "dropping WorkResponse from blackholed scout peer");
-                                    continue;
+                            let acceptance = match serde_json::from_slice::<WorkResponse>(&message.data) {
+                                Ok(result) if result.created_at_ms.is_some_and(|t| now_ms().saturating_sub(t) > STALE_GOSSIP_MS) => {
+                                    gossipsub::MessageAcceptance::Ignore
                                 }
-
-                                tracing::info!(
-                                    request_id = %result.request_id,
-                                    peer = %result.peer_id,
-                                    tokens = result.draft_tokens.len(),
-                                    "received WorkResponse via gossipsub"
-                                );
-
-                                // Propagation latency telemetry is intentionally lightweight:
-                                // one saturating subtraction + one atomic increment.
-                                if let Some(created_at_ms) = result.created_at_ms {
-                                    let propagation_ms = now_ms().saturating_sub(created_at_ms) as u64;
-                                    state.gossipsub_latency_hist.observe(propagation_ms);
+                                Ok(result) => {
+                                    let peer_is_blackholed = {
+                                        let mut pm = state.peer_manager.lock().await;
+                                        pm.is_blackholed(&result.peer_id)
+                                    };
+                                    if peer_is_blackholed {
+                                        tracing::warn!(peer = %result.peer_id, "dropping WorkResponse from blackholed scout peer");
+                                        let mut pm = state.peer_manager.lock().await;
+                                        let status = pm.report_peer(ScoutPenaltyUpdate {
+                                            peer_id: propagation_source.to_string(),
+                                            accepted: false,
+                                            probability_bound: 1.0,
+                                            reason: Some("forwarded WorkResponse from a blackholed scout peer".into()),
+                                        });
+                                        drop(pm);
+                                        persist_scout_status(&state, &status).await;
+                                        gossipsub::MessageAcceptance::Reject
+                                    } else {
+                                        tracing::info!(
+                                            request_id = %result.request_id,
+                                            peer = %result.peer_id,
+                                            tokens = result.draft_tokens.len(),
+                                            "received WorkResponse via gossipsub"
+                                        );
+
+                                        // Propagation latency telemetry is intentionally lightweight:
+                                        // one saturating subtraction + one atomic increment.
+                                        if let Some(created_at_ms) = result.created_at_ms {
+                                            let propagation_ms = now_ms().saturating_sub(created_at_ms) as u64;
+                                            state.gossipsub_latency_hist.observe(propagation_ms);
+                                        }
+
+                                        if let Some(load) = result.current_load {
+                                            let mut pm = state.peer_manager.lock().await;
+                                            if pm.peers().contains_key(&result.peer_id) {
+                                                pm.update_connection_state(&result.peer_id, |info| {
+                                                    info.reported_load = Some(load);
+                                                });
+                                            }
+                                        }
+
+                                        let mut q = state.results.lock().await;
+                                        q.push_back(result);
+                                        while q.len() > 128 { q.pop_front(); }
+                                        gossipsub::MessageAcceptance::Accept
+                                    }
                                 }
-
-                                let mut q = state.results.lock().await;
-                                q.push_back(result);
-                                while q.len() > 128 { q.pop_front(); }
-                            }
+                                Err(e) => {
+                                    tracing::warn!(%e, "malformed WorkResponse gossip payload; rejecting");
+                                    let mut pm = state.peer_manager.lock().await;
+                                    let status = pm.report_peer(ScoutPenaltyUpdate {
+                                        peer_id: propagation_source.to_string(),
+                                        accepted: false,
+                                        probability_bound: 1.0,
+                                        reason: Some(format!("malformed WorkResponse: {e}")),
+                                    });
+                                    drop(pm);
+                                    persist_scout_status(&state, &status).await;
+                                    gossipsub::MessageAcceptance::Reject
+                                }
+                            };
+                            let _ = swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                                &message_id,
+                                &propagation_source,
+                                acceptance,
+                            );
                         } else if message.topic == forward_topic.hash() || message.topic == backward_topic.hash() {
-                            match serde_json::from_slice::<TrainingGossipPacket>(&message.data) {
-                                Ok(TrainingGossipPacket::ForwardPass(packet)) => {
-                                    tracing::info!(
-                                        request_id = %packet.request_id,
-                                        step_id = %packet.step_id,
-                                        tensor = %packet.tensor_name,
-                                        source_peer = %packet.source_peer_id,
-                                        target_peer = ?packet.target_peer_id,
-                                        has_chunk = packet.chunk.is_some(),
-                                        has_blob_ref = packet.blob_ref.is_some(),
-                                        "received forward-pass activation packet"
-                                    );
+                            let acceptance = match serde_json::from_slice::<TrainingGossipPacket>(&message.data) {
+                                Ok(packet) => {
+                                    let (source_peer_id, created_at_ms) = match &packet {
+                                        TrainingGossipPacket::ForwardPass(p) => (p.source_peer_id.clone(), p.created_at_ms),
+                                        TrainingGossipPacket::BackwardPass(p) => (p.source_peer_id.clone(), p.created_at_ms),
+                                    };
+
+                                    if created_at_ms.is_some_and(|t| now_ms().saturating_sub(t) > STALE_GOSSIP_MS) {
+                                        gossipsub::MessageAcceptance::Ignore
+                                    } else {
+                                        let source_is_blackholed = {
+                                            let mut pm = state.peer_manager.lock().await;
+                                            pm.is_blackholed(&source_peer_id)
+                                        };
+                                        if source_is_blackholed {
+                                            tracing::warn!(peer = %source_peer_id, "dropping training gossip packet from blackholed source peer");
+                                            let mut pm = state.peer_manager.lock().await;
+                                            let status = pm.report_peer(ScoutPenaltyUpdate {
+                                                peer_id: propagation_source.to_string(),
+                                                accepted: false,
+                                                probability_bound: 1.0,
+                                                reason: Some("forwarded training packet from a blackholed source peer".into()),
+                                            });
+                                            drop(pm);
+                                            persist_scout_status(&state, &status).await;
+                                            gossipsub::MessageAcceptance::Reject
+                                        } else {
+                                            match packet {
+                                                TrainingGossipPacket::ForwardPass(packet) => {
+                                                    tracing::info!(
+                                                        request_id = %packet.request_id,
+                                                        step_id = %packet.step_id,
+                                                        tensor = %packet.tensor_name,
+                                                        source_peer = %packet.source_peer_id,
+                                                        target_peer = ?packet.target_peer_id,
+                                                        has_chunk = packet.chunk.is_some(),
+                                                        has_blob_ref = packet.blob_ref.is_some(),
+                                                        "received forward-pass activation packet"
+                                                    );
+
+                                                    request_missing_chunks(
+                                                        &state,
+                                                        &mut swarm,
+                                                        &mut pending_chunk_fetches,
+                                                        &packet.request_id,
+                                                        &packet.step_id,
+                                                        &packet.source_peer_id,
+                                                        &packet.tensor_name,
+                                                        packet.shape.clone(),
+                                                        packet.format.clone(),
+                                                        packet.chunk.as_ref(),
+                                                    ).await;
+                                                }
+                                                TrainingGossipPacket::BackwardPass(packet) => {
+                                                    tracing::info!(
+                                                        request_id = %packet.request_id,
+                                                        step_id = %packet.step_id,
+                                                        microbatch_id = %packet.microbatch_id,
+                                                        layer = %packet.layer_path,
+                                                        tensor = %packet.tensor_name,
+                                                        source_peer = %packet.source_peer_id,
+                                                        target_peer = ?packet.target_peer_id,
+                                                        has_chunk = packet.chunk.is_some(),
+                                                        has_blob_ref = packet.blob_ref.is_some(),
+                                                        "received backward-pass gradient packet"
+                                                    );
+
+                                                    request_missing_chunks(
+                                                        &state,
+                                                        &mut swarm,
+                                                        &mut pending_chunk_fetches,
+                                                        &packet.request_id,
+                                                        &packet.step_id,
+                                                        &packet.source_peer_id,
+                                                        &packet.tensor_name,
+                                                        packet.shape.clone(),
+                                                        packet.format.clone(),
+                                                        packet.chunk.as_ref(),
+                                                    ).await;
+
+                                                    // Scaffold only: retain the latest gradient packets until
+                                                    // training routing logic is implemented.
+                                                    let mut gradients = state.backward_passes.lock().await;
+                                                    gradients.push_back(packet);
+                                                    while gradients.len() > 128 { gradients.pop_front(); }
+                                                }
+                                            }
+                                            gossipsub::MessageAcceptance::Accept
+                                        }
+                                    }
                                 }
-                                Ok(TrainingGossipPacket::BackwardPass(packet)) => {
-                                    tracing::info!(
-                                        request_id = %packet.request_id,
-                                        step_id = %packet.step_id,
-                                        microbatch_id = %packet.microbatch_id,
-                                        layer = %packet.layer_path,
-                                        tensor = %packet.tensor_name,
-                                        source_peer = %packet.source_peer_id,
-                                        target_peer = ?packet.target_peer_id,
-                                        has_chunk = packet.chunk.is_some(),
-                                        has_blob_ref = packet.blob_ref.is_some(),
-                                        "received backward-pass gradient packet"
-                                    );
-
-                                    // Scaffold only: retain the latest gradient packets until
-                                    // training routing logic is implemented.
-                                    let mut gradients = state.backward_passes.lock().await;
-                                    gradients.push_back(packet);
-                                    while gradients.len() > 128 { gradients.pop_front(); }
+                                Err(e) => {
+                                    tracing::warn!(%e, "malformed training gossip packet; rejecting");
+                                    let mut pm = state.peer_manager.lock().await;
+                                    let status = pm.report_peer(ScoutPenaltyUpdate {
+                                        peer_id: propagation_source.to_string(),
+                                        accepted: false,
+                                        probability_bound: 1.0,
+                                        reason: Some(format!("malformed training gossip packet: {e}")),
+                                    });
+                                    drop(pm);
+                                    persist_scout_status(&state, &status).await;
+                                    gossipsub::MessageAcceptance::Reject
+                                }
+                            };
+                            let _ = swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                                &message_id,
+                                &propagation_source,
+                                acceptance,
+                            );
+                        } else if message.topic == work_topic.hash() {
+                            let acceptance = match decompress_work_request(&message.data) {
+                                Ok(req) if req.created_at_ms.is_some_and(|t| now_ms().saturating_sub(t) > STALE_GOSSIP_MS) => {
+                                    gossipsub::MessageAcceptance::Ignore
                                 }
+                                Ok(req) => match validate_work_request(&req) {
+                                    Ok(()) => {
+                                        if let Some(created_at_ms) = req.created_at_ms {
+                                            let propagation_ms = now_ms().saturating_sub(created_at_ms) as u64;
+                                            state.work_request_latency_hist.observe(propagation_ms);
+                                        }
+                                        tracing::info!(id = %req.request_id, "received WorkRequest via gossipsub");
+                                        let mut incoming = state.incoming_work.lock().await;
+                                        incoming.push_back(req);
+                                        while incoming.len() > 128 { incoming.pop_front(); }
+                                        gossipsub::MessageAcceptance::Accept
+                                    }
+                                    Err(detail) => {
+                                        tracing::warn!(id = %req.request_id, %detail, "rejecting invalid WorkRequest");
+                                        let mut pm = state.peer_manager.lock().await;
+                                        let status = pm.report_peer(ScoutPenaltyUpdate {
+                                            peer_id: propagation_source.to_string(),
+                                            accepted: false,
+                                            probability_bound: 1.0,
+                                            reason: Some(format!("forwarded invalid WorkRequest: {detail}")),
+                                        });
+                                        drop(pm);
+                                        persist_scout_status(&state, &status).await;
+                                        gossipsub::MessageAcceptance::Reject
+                                    }
+                                },
                                 Err(e) => {
-                                    tracing::warn!(%e, "invalid training gossip packet; ignoring");
+                                    tracing::warn!(%e, "malformed/poisoned WorkRequest gossip payload; rejecting");
+                                    let mut pm = state.peer_manager.lock().await;
+                                    let status = pm.report_peer(ScoutPenaltyUpdate {
+                                        peer_id: propagation_source.to_string(),
+                                        accepted: false,
+                                        probability_bound: 1.0,
+                                        reason: Some(format!("malformed WorkRequest: {e}")),
+                                    });
+                                    drop(pm);
+                                    persist_scout_status(&state, &status).await;
+                                    gossipsub::MessageAcceptance::Reject
                                 }
-                            }
+                            };
+                            let _ = swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                                &message_id,
+                                &propagation_source,
+                                acceptance,
+                            );
+                        } else {
+                            // Topics we don't consume (e.g. `auction.prompt`)
+                            // still need a validation decision under
+                            // `validate_messages()`; accept so the mesh keeps
+                            // propagating them normally.
+                            let _ = swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                                &message_id,
+                                &propagation_source,
+                                gossipsub::MessageAcceptance::Accept,
+                            );
                         }
                     }
 
                     // ── request/response: work forwarding ──
-                    SwarmEvent::Behaviour(ShardBehaviourEvent::ControlWork(
-                        request_response::Event::Message { message, .. },
-                    )) => {
-                        if let request_response::Message::Request { request, channel, .. } = message {
-                            tracing::info!(id = %request.request_id, "work request via req/resp → publishing to gossipsub");
-                            if let Ok(payload) = serde_json::to_vec(&request) {
-                                let _ = swarm.behaviour_mut().gossipsub.publish(work_topic.clone(), payload);
+                    SwarmEvent::Behaviour(ShardBehaviourEvent::ControlWork(event)) => {
+                        match event {
+                            request_response::Event::Message { peer, message, .. } => {
+                                match message {
+                                    request_response::Message::Request { request, channel, .. } => {
+                                        tracing::info!(id = %request.request_id, "work request via req/resp → publishing to gossipsub");
+                                        if let Ok(payload) = compress_work_request(&request) {
+                                            let _ = swarm.behaviour_mut().gossipsub.publish(work_topic.clone(), payload);
+                                        }
+                                        let _ = swarm.behaviour_mut().control_work.send_response(
+                                            channel,
+                                            "published shard-work".to_string(),
+                                        );
+                                    }
+                                    request_response::Message::Response { request_id, .. } => {
+                                        if pending_dispatches.remove(&request_id).is_some() {
+                                            tracing::info!(%peer, "directly dispatched work request accepted");
+                                            let mut pm = state.peer_manager.lock().await;
+                                            let status = pm.report_peer(ScoutPenaltyUpdate {
+                                                peer_id: peer.to_string(),
+                                                accepted: true,
+                                                probability_bound: 1.0,
+                                                reason: None,
+                                            });
+                                            drop(pm);
+                                            persist_scout_status(&state, &status).await;
+                                        }
+                                    }
+                                }
                             }
-                            let _ = swarm.behaviour_mut().control_work.send_response(
-                                channel,
-                                "published shard-work".to_string(),
-                            );
+                            request_response::Event::OutboundFailure { peer, request_id, error, .. } => {
+                                if let Some(mut dispatch) = pending_dispatches.remove(&request_id) {
+                                    tracing::warn!(%peer, %error, id = %dispatch.request.request_id, "direct dispatch failed, falling back to next candidate");
+                                    let mut pm = state.peer_manager.lock().await;
+                                    let status = pm.report_peer(ScoutPenaltyUpdate {
+                                        peer_id: peer.to_string(),
+                                        accepted: false,
+                                        probability_bound: 1.0,
+                                        reason: Some(format!("dispatch failure: {error}")),
+                                    });
+                                    drop(pm);
+                                    persist_scout_status(&state, &status).await;
+
+                                    let remaining = dispatch::next_after_failure(&dispatch.remaining_candidates, &peer.to_string());
+                                    if let Some((next, rest)) = remaining.split_first() {
+                                        if let Ok(target) = next.peer_id.parse::<PeerId>() {
+                                            let id = swarm.behaviour_mut().control_work.send_request(&target, dispatch.request.clone());
+                                            dispatch.remaining_candidates = rest.to_vec();
+                                            pending_dispatches.insert(id, dispatch);
+                                            continue;
+                                        }
+                                    }
+
+                                    if let Ok(payload) = compress_work_request(&dispatch.request) {
+                                        let _ = swarm.behaviour_mut().gossipsub.publish(work_topic.clone(), payload);
+                                        tracing::info!(id = %dispatch.request.request_id, "exhausted dispatch candidates, broadcast to gossipsub");
+                                    }
+                                }
+                            }
+                            _ => {}
                         }
                     }
 
-                    // ── handshake (PING/PONG) ──
+                    // ── handshake: signed capability attestation + liveness ping ──
                     SwarmEvent::Behaviour(ShardBehaviourEvent::Handshake(
                         request_response::Event::Message { peer, message, .. },
                     )) => {
                         match message {
                             request_response::Message::Request { request, channel, .. } => {
-                                if request.kind == "PING" {
-                                    let latency = now_ms().saturating_sub(request.sent_at_ms);
-                                    tracing::info!(%peer, %latency, "PING → PONG");
-                                    let pong = Heartbeat { kind: "PONG".into(), sent_at_ms: now_ms() };
-                                    let _ = swarm.behaviour_mut().handshake.send_response(channel, pong);
-
-                                    let mut peers = state.peers.lock().await;
-                                    if let Some(info) = peers.get_mut(&peer.to_string()) {
-                                        info.verified = true;
-                                        info.last_seen_at = now_ms();
+                                match request {
+                                    HandshakeMessage::Ping(ping) => {
+                                        let latency = now_ms().saturating_sub(ping.sent_at_ms);
+                                        tracing::info!(%peer, %latency, "PING → PONG");
+                                        let pong = HandshakeMessage::Ping(Heartbeat {
+                                            kind: "PONG".into(),
+                                            sent_at_ms: now_ms(),
+                                        });
+                                        let _ = swarm.behaviour_mut().handshake.send_response(channel, pong);
+
+                                        let mut pm = state.peer_manager.lock().await;
+                                        if pm.peers().contains_key(&peer.to_string()) {
+                                            pm.update_connection_state(&peer.to_string(), |info| {
+                                                info.last_seen_at = now_ms();
+                                            });
+                                        }
+                                    }
+                                    HandshakeMessage::Attestation(attestation) => {
+                                        let accepted = verify_capability_attestation(&attestation, &peer);
+                                        let mut pm = state.peer_manager.lock().await;
+                                        if pm.peers().contains_key(&peer.to_string()) {
+                                            pm.update_connection_state(&peer.to_string(), |info| {
+                                                info.last_seen_at = now_ms();
+                                                if accepted {
+                                                    info.verified = true;
+                                                    info.reported_capacity = Some(attestation.capacity);
+                                                } else {
+                                                    info.handshake_failures += 1;
+                                                }
+                                            });
+                                            if !accepted {
+                                                tracing::warn!(%peer, "rejected capability attestation with invalid signature");
+                                            }
+                                        }
+                                        drop(pm);
+
+                                        let our_attestation = sign_capability_attestation(
+                                            &id_keys,
+                                            state.capacity.load(Ordering::Relaxed),
+                                            SUPPORTED_TENSOR_FORMATS.to_vec(),
+                                            env!("CARGO_PKG_VERSION").to_string(),
+                                        );
+                                        let _ = swarm.behaviour_mut().handshake.send_response(
+                                            channel,
+                                            HandshakeMessage::Attestation(our_attestation),
+                                        );
                                     }
+                                    HandshakeMessage::GetPeers => {
+                                        let mut pm = state.peer_manager.lock().await;
+                                        let requester_addrs = pm
+                                            .peers()
+                                            .get(&peer.to_string())
+                                            .map(|info| info.addrs.clone())
+                                            .unwrap_or_default();
+
+                                        let mut exclude: HashSet<String> =
+                                            requester_addrs.into_iter().collect();
+
+                                        let all_peer_addrs: Vec<(String, Vec<String>)> = pm
+                                            .peers()
+                                            .iter()
+                                            .map(|(id, info)| (id.clone(), info.addrs.clone()))
+                                            .collect();
+                                        for (peer_id, addrs) in all_peer_addrs {
+                                            if pm.is_blackholed(&peer_id) {
+                                                exclude.extend(addrs);
+                                            }
+                                        }
+
+                                        let known = pm.known().to_vec();
+                                        drop(pm);
+                                        let sample =
+                                            sample_diverse_peers(&known, &exclude, GET_PEERS_SAMPLE_LIMIT);
+                                        tracing::debug!(%peer, count = sample.len(), "serving GetPeers request");
+                                        let _ = swarm
+                                            .behaviour_mut()
+                                            .handshake
+                                            .send_response(channel, HandshakeMessage::Peers(sample));
+                                    }
+                                    HandshakeMessage::Peers(_) => {}
                                 }
                             }
                             request_response::Message::Response { response, request_id } => {
-                                tracing::info!(%peer, kind = %response.kind, "handshake response");
                                 pending_handshakes.remove(&request_id);
-                                let mut peers = state.peers.lock().await;
-                                if let Some(info) = peers.get_mut(&peer.to_string()) {
-                                    info.last_seen_at = now_ms();
-                                    if response.kind == "PONG" {
-                                        info.verified = true;
+                                match response {
+                                    HandshakeMessage::Ping(pong) => {
+                                        tracing::info!(%peer, kind = %pong.kind, "handshake ping response");
+                                        let mut pm = state.peer_manager.lock().await;
+                                        if pm.peers().contains_key(&peer.to_string()) {
+                                            pm.update_connection_state(&peer.to_string(), |info| {
+                                                info.last_seen_at = now_ms();
+                                            });
+                                        }
+                                    }
+                                    HandshakeMessage::Attestation(attestation) => {
+                                        let accepted = verify_capability_attestation(&attestation, &peer);
+                                        let mut pm = state.peer_manager.lock().await;
+                                        if pm.peers().contains_key(&peer.to_string()) {
+                                            pm.update_connection_state(&peer.to_string(), |info| {
+                                                info.last_seen_at = now_ms();
+                                                if accepted {
+                                                    info.verified = true;
+                                                    info.reported_capacity = Some(attestation.capacity);
+                                                } else {
+                                                    info.handshake_failures += 1;
+                                                }
+                                            });
+                                            if !accepted {
+                                                tracing::warn!(%peer, "rejected capability attestation with invalid signature");
+                                            }
+                                        }
+                                    }
+                                    HandshakeMessage::GetPeers => {}
+                                    HandshakeMessage::Peers(addrs) => {
+                                        let valid: Vec<String> = addrs
+                                            .into_iter()
+                                            .filter(|addr| addr.parse::<Multiaddr>().is_ok())
+                                            .collect();
+                                        tracing::debug!(%peer, count = valid.len(), "received GetPeers response");
+
+                                        let mut pm = state.peer_manager.lock().await;
+                                        let known = pm.known_mut();
+                                        known.extend(valid);
+                                        *known = unique_addrs(known.clone());
+                                        let known_snapshot = known.clone();
+                                        drop(pm);
+                                        save_persisted_peers(&known_peers_path, &known_snapshot).await;
                                     }
                                 }
                             }
                         }
                     }
 
+                    // ── block transfer: serve/receive tensor chunks ──
+                    SwarmEvent::Behaviour(ShardBehaviourEvent::BlockTransfer(
+                        request_response::Event::Message { peer, message, .. },
+                    )) => {
+                        match message {
+                            request_response::Message::Request { request, channel, .. } => {
+                                let transfers = state.transfers.lock().await;
+                                let stored = transfers.chunk_for_serving(
+                                    &request.request_id,
+                                    &request.step_id,
+                                    &request.tensor_name,
+                                    request.chunk_index,
+                                );
+                                drop(transfers);
+                                let response = block_transfer::ChunkResponse {
+                                    chunk_index: request.chunk_index,
+                                    data: stored.as_ref().map(|(data, _)| data.clone()),
+                                    checksum_blake3: stored.map(|(_, checksum)| checksum),
+                                };
+                                let _ = swarm.behaviour_mut().block_transfer.send_response(channel, response);
+                            }
+                            request_response::Message::Response { request_id, response } => {
+                                let Some(req) = pending_chunk_fetches.remove(&request_id) else {
+                                    continue;
+                                };
+                                let mut transfers = state.transfers.lock().await;
+                                if let (Some(data), Some(checksum)) = (&response.data, &response.checksum_blake3) {
+                                    if transfers.ingest_chunk(
+                                        &req.request_id,
+                                        &req.step_id,
+                                        &req.tensor_name,
+                                        response.chunk_index,
+                                        data,
+                                        checksum,
+                                    ) {
+                                        tracing::debug!(%peer, chunk_index = response.chunk_index, "verified tensor chunk received");
+                                    } else {
+                                        tracing::warn!(%peer, chunk_index = response.chunk_index, "tensor chunk failed checksum verification");
+                                    }
+                                } else {
+                                    tracing::debug!(%peer, chunk_index = response.chunk_index, "peer does not have requested chunk yet");
+                                    transfers.release_outstanding(
+                                        &req.request_id,
+                                        &req.step_id,
+                                        &req.tensor_name,
+                                        response.chunk_index,
+                                    );
+                                }
+
+                                let still_missing = transfers.next_missing_chunks(&req.request_id, &req.step_id, &req.tensor_name);
+                                drop(transfers);
+                                for chunk_index in still_missing {
+                                    let retry = block_transfer::ChunkRequest {
+                                        request_id: req.request_id.clone(),
+                                        step_id: req.step_id.clone(),
+                                        tensor_name: req.tensor_name.clone(),
+                                        chunk_index,
+                                    };
+                                    let outbound_id = swarm.behaviour_mut().block_transfer.send_request(&peer, retry.clone());
+                                    pending_chunk_fetches.insert(outbound_id, retry);
+                                }
+                            }
+                        }
+                    }
+
+                    // ── Basalt push/pull exchange ──
+                    SwarmEvent::Behaviour(ShardBehaviourEvent::Sampling(
+                        request_response::Event::Message { peer, message, .. },
+                    )) => {
+                        match message {
+                            request_response::Message::Request { request, channel, .. } => {
+                                let mut view = state.sampling_view.lock().await;
+                                let reply = peer_sampling::SamplingPushPull { view: view.sample() };
+                                view.merge(request.view);
+                                drop(view);
+                                let _ = swarm.behaviour_mut().sampling.send_response(channel, reply);
+                                tracing::debug!(%peer, "sampling push/pull request handled");
+                            }
+                            request_response::Message::Response { response, .. } => {
+                                state.sampling_view.lock().await.merge(response.view);
+                                tracing::debug!(%peer, "sampling push/pull response merged");
+                            }
+                        }
+                    }
+
                     // ── verify protocol ──
                     SwarmEvent::Behaviour(ShardBehaviourEvent::Verify(event)) => {
                         tracing::debug!(?event, "verify protocol event");
@@ -1152,8 +2813,15 @@ async fn main() -> Result<()> {
                         tracing::debug!(?event, "kademlia event");
                     }
 
-                    // Note: relay client disabled - libp2p API changed
-                    // SwarmEvent::Behaviour(ShardBehaviourEvent::RelayClient(event)) => { ... }
+                    // ── relay client ──
+                    SwarmEvent::Behaviour(ShardBehaviourEvent::RelayClient(event)) => {
+                        match event {
+                            relay::client::Event::ReservationReqAccepted { relay_peer_id, .. } => {
+                                tracing::info!(%relay_peer_id, "relay client: reservation accepted");
+                            }
+                            _ => {}
+                        }
+                    }
 
                     // ── relay server ──
                     SwarmEvent::Behaviour(ShardBehaviourEvent::RelayServer(event)) => {
@@ -1168,11 +2836,26 @@ async fn main() -> Result<()> {
                         }
                     }
 
-                    // ── dcutr ──
+                    // ── dcutr: record hole-punch success/failure per peer so
+                    // the reconnect loop can prefer a direct address over a
+                    // relayed one for peers we've already punched through to ──
                     SwarmEvent::Behaviour(ShardBehaviourEvent::Dcutr(event)) => {
-                        let _ = event;
-                        // dcutr events - simplified for compatibility
-                        tracing::debug!("dcutr event: {:?}", event);
+                        let succeeded = event.result.is_ok();
+                        if succeeded {
+                            tracing::info!(peer = %event.remote_peer_id, "dcutr: hole punch succeeded");
+                        } else {
+                            tracing::warn!(
+                                peer = %event.remote_peer_id,
+                                error = ?event.result,
+                                "dcutr: hole punch failed"
+                            );
+                        }
+                        let mut pm = state.peer_manager.lock().await;
+                        if pm.peers().contains_key(&event.remote_peer_id.to_string()) {
+                            pm.update_connection_state(&event.remote_peer_id.to_string(), |peer| {
+                                peer.hole_punch_succeeded = Some(succeeded);
+                            });
+                        }
                     }
 
                     // ── autonat ──
@@ -1210,11 +2893,25 @@ async fn main() -> Result<()> {
                         }
                     }
 
-                    // ── ping ──
+                    // ── ping: feed RTT samples into the peering manager ──
                     SwarmEvent::Behaviour(ShardBehaviourEvent::Ping(event)) => {
-                        let _ = event;
-                        // ping events - simplified for compatibility
-                        tracing::debug!("ping event: {:?}", event);
+                        if let Ok(rtt) = &event.result {
+                            let addr = state
+                                .peer_manager
+                                .lock()
+                                .await
+                                .peers()
+                                .get(&event.peer.to_string())
+                                .and_then(|info| info.addrs.first().cloned());
+                            if let Some(addr) = addr {
+                                state
+                                    .peering
+                                    .lock()
+                                    .await
+                                    .record_rtt(&addr, rtt.as_millis() as f64);
+                            }
+                        }
+                        tracing::debug!(peer = %event.peer, "ping event: {:?}", event.result);
                     }
 
                     // ── new listen addresses → update topology ──
@@ -1249,6 +2946,7 @@ async fn main() -> Result<()> {
                             "capacity": topo.capacity,
                             "load": topo.load,
                             "latency_ms": topo.latency_ms,
+                            "bandwidth": state.bandwidth.stats(),
                         });
                         let _ = tokio::fs::write(&topo_path, topo_json.to_string()).await;
                     }
@@ -1256,8 +2954,8 @@ async fn main() -> Result<()> {
                     // ── peer connections ──
                     SwarmEvent::ConnectionEstablished { peer_id, endpoint, .. } => {
                         let should_reject = {
-                            let mut penalties = state.scout_penalties.lock().await;
-                            penalties.is_blackholed(&peer_id.to_string())
+                            let pm = state.peer_manager.lock().await;
+                            should_reject_peer_connection(&pm, &peer_id.to_string())
                         };
                         if should_reject {
                             tracing::warn!(%peer_id, "rejecting blackholed peer at transport layer");
@@ -1265,40 +2963,131 @@ async fn main() -> Result<()> {
                             continue;
                         }
 
-                        tracing::info!(%peer_id, ?endpoint, "peer connected");
                         let remote_addr = endpoint.get_remote_address().to_string();
+                        let inbound = endpoint.is_listener();
+
+                        // IP/CIDR blacklist: catches a re-keyed scout
+                        // (fresh `PeerId`, same address) that `ScoutPenaltyBook`
+                        // alone can't recognize.
+                        if let Some(ip) = ip_blacklist::extract_ip_from_multiaddr(&remote_addr) {
+                            let blocked = state.ip_blacklist.lock().await.is_blocked(ip, now_ms());
+                            if blocked {
+                                tracing::warn!(%peer_id, %ip, "rejecting connection: IP/CIDR blacklisted");
+                                let _ = swarm.disconnect_peer_id(peer_id);
+                                continue;
+                            }
+                        }
+
+                        // `deny_unreserved` pins the node to a fixed backbone:
+                        // once enabled, drop any inbound connection from a
+                        // peer that isn't in the reserved set outright.
+                        if inbound {
+                            let is_reserved = state
+                                .connection_budget
+                                .lock()
+                                .await
+                                .is_reserved(&peer_id.to_string());
+                            let deny_unreserved =
+                                state.connection_budget.lock().await.deny_unreserved;
+                            if deny_unreserved && !is_reserved {
+                                tracing::warn!(%peer_id, "rejecting non-reserved peer: deny_unreserved is enabled");
+                                let _ = swarm.disconnect_peer_id(peer_id);
+                                continue;
+                            }
+                        }
+
+                        tracing::info!(%peer_id, ?endpoint, "peer connected");
 
                         {
-                            let mut peers = state.peers.lock().await;
-                            peers.insert(
-                                peer_id.to_string(),
-                                PeerInfo {
-                                    peer_id: peer_id.to_string(),
-                                    connected_at: now_ms(),
-                                    last_seen_at: now_ms(),
-                                    addrs: vec![remote_addr.clone()],
-                                    verified: false,
-                                    handshake_failures: 0,
-                                },
-                            );
+                            let mut pm = state.peer_manager.lock().await;
+                            let info = pm.update_connection_state(&peer_id.to_string(), |info| {
+                                info.connected_at = now_ms();
+                                info.last_seen_at = now_ms();
+                                info.addrs = vec![remote_addr.clone()];
+                                info.inbound = inbound;
+                            });
+                            persist_peer_metadata(&state, info);
+                        }
+
+                        // Peer-excess eviction: `connection_limits` already
+                        // hard-denies beyond `max_connections`, while the
+                        // inbound budget is `max_connections` scaled down by
+                        // `connection_excess_factor`, keeping the difference
+                        // for outbound-only peers — so once inbound
+                        // connections exceed that budget, drop the
+                        // lowest-value inbound peer rather than denying the
+                        // newcomer outright.
+                        if inbound {
+                            let over_budget = {
+                                let pm = state.peer_manager.lock().await;
+                                let budget = state.connection_budget.lock().await;
+                                pm.peers().values().filter(|p| p.inbound).count() as u32
+                                    > budget.inbound_budget()
+                            };
+                            if over_budget {
+                                let evict_peer_id = {
+                                    let mut pm = state.peer_manager.lock().await;
+                                    let budget = state.connection_budget.lock().await;
+                                    let peers = pm.peers().clone();
+                                    connection_budget::lowest_value_inbound_peer(
+                                        &peers,
+                                        pm.penalties_mut(),
+                                        &budget,
+                                    )
+                                };
+                                if let Some(evict) = evict_peer_id {
+                                    if let Ok(evict_id) = evict.parse::<PeerId>() {
+                                        tracing::info!(
+                                            peer = %evict_id,
+                                            "peer-excess eviction: pruning lowest-value inbound peer"
+                                        );
+                                        let _ = swarm.disconnect_peer_id(evict_id);
+                                    }
+                                }
+                            }
                         }
 
                         {
-                            let mut known = state.known_peers.lock().await;
-                            known.push(remote_addr);
+                            let mut pm = state.peer_manager.lock().await;
+                            let known = pm.known_mut();
+                            known.push(remote_addr.clone());
                             *known = unique_addrs(known.clone());
-                            save_persisted_peers(&known_peers_path, &known).await;
+                            let known_snapshot = known.clone();
+                            drop(pm);
+                            save_persisted_peers(&known_peers_path, &known_snapshot).await;
                         }
 
-                        let req = Heartbeat { kind: "PING".into(), sent_at_ms: now_ms() };
-                        let id = swarm.behaviour_mut().handshake.send_request(&peer_id, req);
+                        let attestation = sign_capability_attestation(
+                            &id_keys,
+                            state.capacity.load(Ordering::Relaxed),
+                            SUPPORTED_TENSOR_FORMATS.to_vec(),
+                            env!("CARGO_PKG_VERSION").to_string(),
+                        );
+                        let id = swarm
+                            .behaviour_mut()
+                            .handshake
+                            .send_request(&peer_id, HandshakeMessage::Attestation(attestation));
                         pending_handshakes.insert(id, peer_id);
+
+                        state.peering.lock().await.mark_up(&remote_addr);
+
+                        state.sampling_view.lock().await.observe(peer_sampling::SampledPeer {
+                            peer_id: peer_id.to_string(),
+                            addr: remote_addr,
+                        });
                     }
 
                     SwarmEvent::ConnectionClosed { peer_id, .. } => {
                         tracing::info!(%peer_id, "peer disconnected");
-                        let mut peers = state.peers.lock().await;
-                        peers.remove(&peer_id.to_string());
+                        let removed = state.peer_manager.lock().await.remove_peer(&peer_id.to_string());
+                        if let Some(mut info) = removed {
+                            info.last_seen_at = now_ms();
+                            persist_peer_metadata(&state, &info);
+                            let mut peering = state.peering.lock().await;
+                            for addr in &info.addrs {
+                                peering.mark_down(addr);
+                            }
+                        }
                     }
 
                     SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
@@ -1314,10 +3103,14 @@ async fn main() -> Result<()> {
 
 #[cfg(test)]
 mod tests {
+    use super::peer_manager::PeerManager;
     use super::{
-        should_reject_peer_connection, unique_addrs, validate_work_request, LatencyHistogram,
-        ScoutPenaltyBook, ScoutPenaltyUpdate, WorkRequest,
+        compress_work_request, connection_budget, decompress_work_request, ip_blacklist,
+        sample_diverse_peers, should_reject_peer_connection, unique_addrs, validate_work_request,
+        now_ms, LatencyHistogram, PeerInfo, ScoutPenaltyBook, ScoutPenaltyUpdate, WorkRequest,
     };
+    use std::collections::{HashMap, HashSet};
+    use std::sync::Arc;
 
     #[test]
     fn unique_addrs_removes_duplicates() {
@@ -1365,6 +3158,27 @@ mod tests {
         };
         assert!(validate_work_request(&bad).is_err());
     }
+
+    #[test]
+    fn work_request_compression_roundtrips() {
+        let req = WorkRequest {
+            request_id: "abc".into(),
+            prompt_context: "hello ".repeat(500),
+            min_tokens: 4,
+            created_at_ms: Some(123),
+        };
+        let compressed = compress_work_request(&req).expect("compress");
+        assert!(compressed.len() < req.prompt_context.len());
+        let decompressed = decompress_work_request(&compressed).expect("decompress");
+        assert_eq!(decompressed.request_id, req.request_id);
+        assert_eq!(decompressed.prompt_context, req.prompt_context);
+        assert_eq!(decompressed.min_tokens, req.min_tokens);
+    }
+
+    #[test]
+    fn decompress_work_request_rejects_garbage() {
+        assert!(decompress_work_request(b"not zstd data").is_err());
+    }
     #[test]
     fn test_malicious_scout_blacklist_trigger() {
         let mut penalties = ScoutPenaltyBook::default();
@@ -1450,24 +3264,357 @@ mod tests {
 
     #[test]
     fn test_blacklist_enforcement_rejects_connection() {
-        let mut penalties = ScoutPenaltyBook::default();
+        let mut pm = PeerManager::new(HashMap::new(), Vec::new(), ScoutPenaltyBook::default());
         let peer_id = "PeerID_C".to_string();
 
-        penalties.apply_update(ScoutPenaltyUpdate {
+        pm.report_peer(ScoutPenaltyUpdate {
             peer_id: peer_id.clone(),
             accepted: true,
             probability_bound: 1.0e-16,
             reason: None,
         });
+        for _ in 0..5 {
+            pm.report_peer(ScoutPenaltyUpdate {
+                peer_id: peer_id.clone(),
+                accepted: false,
+                probability_bound: 1.0e-12,
+                reason: Some("poisoned".to_string()),
+            });
+        }
+
+        assert!(should_reject_peer_connection(&pm, &peer_id));
+    }
+
+    #[test]
+    fn peer_manager_concurrent_connect_disconnect_penalty_no_deadlock() {
+        let pm = Arc::new(std::sync::Mutex::new(PeerManager::new(
+            HashMap::new(),
+            Vec::new(),
+            ScoutPenaltyBook::default(),
+        )));
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let pm = Arc::clone(&pm);
+                std::thread::spawn(move || {
+                    let peer_id = format!("PeerID_Thread_{i}");
+                    for round in 0..50 {
+                        {
+                            let mut pm = pm.lock().unwrap();
+                            pm.update_connection_state(&peer_id, |info| {
+                                info.last_seen_at = round;
+                            });
+                        }
+                        {
+                            let mut pm = pm.lock().unwrap();
+                            pm.report_peer(ScoutPenaltyUpdate {
+                                peer_id: peer_id.clone(),
+                                accepted: round % 2 == 0,
+                                probability_bound: 1.0e-9,
+                                reason: None,
+                            });
+                        }
+                        {
+                            let mut pm = pm.lock().unwrap();
+                            if round % 10 == 9 {
+                                pm.remove_peer(&peer_id);
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().expect("peer manager thread panicked");
+        }
+
+        let pm = pm.lock().unwrap();
+        for i in 0..8 {
+            let peer_id = format!("PeerID_Thread_{i}");
+            // Each thread's final round (49) doesn't re-insert after its
+            // round-9/19/.../49 removals, so the peer is absent from
+            // `peers()` but its reputation history survived every removal.
+            assert!(pm.peers().get(&peer_id).is_none());
+            assert!(pm.penalties().all_statuses().iter().any(|s| s.peer_id == peer_id));
+        }
+    }
+
+    #[test]
+    fn repeat_offenses_escalate_ban_duration() {
+        let mut penalties = ScoutPenaltyBook::default();
+        let peer_id = "PeerID_Repeat".to_string();
+
+        let ban_until = |penalties: &mut ScoutPenaltyBook, peer_id: &str| -> u128 {
+            for _ in 0..5 {
+                penalties.apply_update(ScoutPenaltyUpdate {
+                    peer_id: peer_id.to_string(),
+                    accepted: false,
+                    probability_bound: 1.0e-12,
+                    reason: Some("misbehaving".to_string()),
+                });
+            }
+            penalties
+                .entries
+                .get(peer_id)
+                .and_then(|entry| entry.banned_until_ms)
+                .unwrap()
+        };
+
+        let now = now_ms();
+        let first_ban_ms = ban_until(&mut penalties, &peer_id) - now;
+
+        // Force the first ban to expire, then earn a second one.
+        penalties.entries.get_mut(&peer_id).unwrap().banned_until_ms = Some(0);
+        assert!(!penalties.is_blackholed(&peer_id));
+
+        let now = now_ms();
+        let second_ban_ms = ban_until(&mut penalties, &peer_id) - now;
+
+        assert!(second_ban_ms > first_ban_ms);
+    }
+
+    #[test]
+    fn ban_expiry_resets_to_probation_score_not_stale_score() {
+        let mut penalties = ScoutPenaltyBook::default();
+        let peer_id = "PeerID_Recovering".to_string();
+
         for _ in 0..5 {
             penalties.apply_update(ScoutPenaltyUpdate {
                 peer_id: peer_id.clone(),
                 accepted: false,
                 probability_bound: 1.0e-12,
+                reason: Some("misbehaving".to_string()),
+            });
+        }
+        assert!(penalties.is_blackholed(&peer_id));
+
+        // Force the ban to have already expired.
+        penalties.entries.get_mut(&peer_id).unwrap().banned_until_ms = Some(0);
+        assert!(!penalties.is_blackholed(&peer_id));
+
+        let entry = penalties.entries.get(&peer_id).unwrap();
+        assert_eq!(entry.score, ScoutPenaltyBook::PROBATION_SCORE);
+    }
+
+    fn test_peer(peer_id: &str, score: Option<f64>, reported_capacity: Option<u32>) -> PeerInfo {
+        PeerInfo {
+            peer_id: peer_id.to_string(),
+            connected_at: 0,
+            last_seen_at: 0,
+            addrs: Vec::new(),
+            verified: true,
+            handshake_failures: 0,
+            reported_capacity,
+            reported_load: None,
+            score,
+            hole_punch_succeeded: None,
+            inbound: true,
+        }
+    }
+
+    #[test]
+    fn inbound_budget_scales_down_by_excess_factor() {
+        let budget = connection_budget::ConnectionBudget {
+            max_total: 200,
+            max_pending_incoming: 50,
+            max_per_peer: 1,
+            excess_factor: 1.25,
+            reserved_peers: Vec::new(),
+            deny_unreserved: false,
+        };
+        assert_eq!(budget.inbound_budget(), 160);
+    }
+
+    #[test]
+    fn lowest_value_inbound_peer_prefers_blackholed_then_score_then_capacity() {
+        let mut peers = HashMap::new();
+        peers.insert(
+            "PeerID_Good".to_string(),
+            test_peer("PeerID_Good", Some(5.0), Some(100)),
+        );
+        peers.insert(
+            "PeerID_LowScore".to_string(),
+            test_peer("PeerID_LowScore", Some(-2.0), Some(100)),
+        );
+        peers.insert(
+            "PeerID_Blackholed".to_string(),
+            test_peer("PeerID_Blackholed", Some(10.0), Some(100)),
+        );
+
+        let mut penalties = ScoutPenaltyBook::default();
+        for _ in 0..5 {
+            penalties.apply_update(ScoutPenaltyUpdate {
+                peer_id: "PeerID_Blackholed".to_string(),
+                accepted: false,
+                probability_bound: 1.0e-12,
                 reason: Some("poisoned".to_string()),
             });
         }
 
-        assert!(should_reject_peer_connection(&mut penalties, &peer_id));
+        let budget = connection_budget::ConnectionBudget {
+            max_total: 200,
+            max_pending_incoming: 50,
+            max_per_peer: 1,
+            excess_factor: 1.25,
+            reserved_peers: Vec::new(),
+            deny_unreserved: false,
+        };
+
+        let evicted = connection_budget::lowest_value_inbound_peer(&peers, &mut penalties, &budget);
+        assert_eq!(evicted, Some("PeerID_Blackholed".to_string()));
+
+        // Without a blackholed peer, the lowest score goes next, and equal
+        // scores fall back to the lower reported capacity.
+        peers.remove("PeerID_Blackholed");
+        peers.insert(
+            "PeerID_LowScoreSmall".to_string(),
+            test_peer("PeerID_LowScoreSmall", Some(-2.0), Some(10)),
+        );
+        let evicted = connection_budget::lowest_value_inbound_peer(&peers, &mut penalties, &budget);
+        assert_eq!(evicted, Some("PeerID_LowScoreSmall".to_string()));
+    }
+
+    #[test]
+    fn lowest_value_inbound_peer_skips_reserved_peers() {
+        let mut peers = HashMap::new();
+        peers.insert(
+            "PeerID_Reserved".to_string(),
+            test_peer("PeerID_Reserved", Some(-99.0), Some(0)),
+        );
+        peers.insert(
+            "PeerID_Other".to_string(),
+            test_peer("PeerID_Other", Some(1.0), Some(50)),
+        );
+
+        let mut penalties = ScoutPenaltyBook::default();
+        let budget = connection_budget::ConnectionBudget {
+            max_total: 200,
+            max_pending_incoming: 50,
+            max_per_peer: 1,
+            excess_factor: 1.25,
+            reserved_peers: vec!["PeerID_Reserved".to_string()],
+            deny_unreserved: false,
+        };
+
+        let evicted = connection_budget::lowest_value_inbound_peer(&peers, &mut penalties, &budget);
+        assert_eq!(evicted, Some("PeerID_Other".to_string()));
+    }
+
+    #[test]
+    fn reserved_peer_add_remove_roundtrip() {
+        let mut budget = connection_budget::ConnectionBudget {
+            max_total: 200,
+            max_pending_incoming: 50,
+            max_per_peer: 1,
+            excess_factor: 1.25,
+            reserved_peers: Vec::new(),
+            deny_unreserved: false,
+        };
+
+        budget.add_reserved_peer("/ip4/10.0.0.1/tcp/4001/p2p/PeerID_Backbone".to_string());
+        assert!(budget.is_reserved("PeerID_Backbone"));
+        assert_eq!(budget.reserved_addrs().len(), 1);
+
+        assert!(budget.remove_reserved_peer("PeerID_Backbone"));
+        assert!(!budget.is_reserved("PeerID_Backbone"));
+        assert!(budget.reserved_addrs().is_empty());
+    }
+
+    #[test]
+    fn ip_blacklist_parse_entry_handles_all_formats() {
+        assert!(matches!(
+            ip_blacklist::parse_entry("10.0.0.1"),
+            Some(ip_blacklist::CidrEntry::Exact(_))
+        ));
+        assert!(matches!(
+            ip_blacklist::parse_entry("10.0.0.1:4001"),
+            Some(ip_blacklist::CidrEntry::Exact(_))
+        ));
+        assert!(matches!(
+            ip_blacklist::parse_entry("10.0.0.0/8"),
+            Some(ip_blacklist::CidrEntry::Network { .. })
+        ));
+        assert!(ip_blacklist::parse_entry("10.0.0.0/33").is_none());
+        assert!(ip_blacklist::parse_entry("not-an-ip").is_none());
+    }
+
+    #[test]
+    fn ip_blacklist_cidr_entry_contains_matches_network() {
+        let entry = ip_blacklist::parse_entry("10.0.0.0/24").unwrap();
+        assert!(entry.contains("10.0.0.42".parse().unwrap()));
+        assert!(!entry.contains("10.0.1.42".parse().unwrap()));
+    }
+
+    #[test]
+    fn ip_blacklist_extract_ip_from_multiaddr_parses_ip4_and_ip6() {
+        assert_eq!(
+            ip_blacklist::extract_ip_from_multiaddr("/ip4/1.2.3.4/tcp/4001"),
+            Some("1.2.3.4".parse().unwrap())
+        );
+        assert_eq!(
+            ip_blacklist::extract_ip_from_multiaddr("/ip6/::1/tcp/4001/ws"),
+            Some("::1".parse().unwrap())
+        );
+        assert_eq!(ip_blacklist::extract_ip_from_multiaddr("/dns4/example.com/tcp/4001"), None);
+    }
+
+    #[test]
+    fn ip_blacklist_is_blocked_checks_static_then_temporary() {
+        let mut blacklist =
+            ip_blacklist::IpBlacklist::new(vec![ip_blacklist::parse_entry("10.0.0.0/8").unwrap()]);
+        assert!(blacklist.is_blocked("10.1.2.3".parse().unwrap(), 0));
+        assert!(!blacklist.is_blocked("192.168.0.1".parse().unwrap(), 0));
+
+        let ip = "192.168.0.1".parse().unwrap();
+        blacklist.escalate(ip, 100);
+        assert!(blacklist.is_blocked(ip, 50));
+        assert!(!blacklist.is_blocked(ip, 150));
+    }
+
+    #[test]
+    fn ip_blacklist_escalate_extends_rather_than_shortens() {
+        let mut blacklist = ip_blacklist::IpBlacklist::new(Vec::new());
+        let ip = "192.168.0.1".parse().unwrap();
+        blacklist.escalate(ip, 1_000);
+        blacklist.escalate(ip, 500);
+        assert!(blacklist.is_blocked(ip, 900));
+    }
+
+    #[test]
+    fn sample_diverse_peers_excludes_and_caps() {
+        let known: Vec<String> = vec![
+            "/ip4/10.0.0.1/tcp/4001".to_string(),
+            "/ip4/10.0.0.2/tcp/4001".to_string(),
+            "/ip4/10.0.0.3/tcp/4001".to_string(),
+        ];
+        let exclude: HashSet<String> = ["/ip4/10.0.0.2/tcp/4001".to_string()].into_iter().collect();
+
+        let sample = sample_diverse_peers(&known, &exclude, 1);
+        assert_eq!(sample.len(), 1);
+        assert!(!exclude.contains(&sample[0]));
+    }
+
+    #[test]
+    fn sample_diverse_peers_spreads_across_subnets_before_repeating() {
+        // Two addresses from the same /16-ish bucket, one from a distinct
+        // subnet: the diverse address should always make the cut when the
+        // sample is capped to one per round.
+        let known: Vec<String> = vec![
+            "/ip4/10.0.0.1/tcp/4001".to_string(),
+            "/ip4/10.0.0.2/tcp/4001".to_string(),
+            "/ip4/192.168.1.1/tcp/4001".to_string(),
+        ];
+        let exclude = HashSet::new();
+
+        for _ in 0..20 {
+            let sample = sample_diverse_peers(&known, &exclude, 2);
+            assert_eq!(sample.len(), 2);
+            let buckets: HashSet<&str> = sample
+                .iter()
+                .map(|addr| if addr.contains("192.168") { "b" } else { "a" })
+                .collect();
+            assert_eq!(buckets.len(), 2, "expected one address from each subnet bucket");
+        }
     }
 }
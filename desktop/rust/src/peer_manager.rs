@@ -0,0 +1,123 @@
+//! Owns connected-peer state, the durable known-address list, and scout
+//! reputation together behind one lock.
+//!
+//! Before this module existed, the swarm loop took `state.peers`,
+//! `state.known_peers`, and `state.scout_penalties` as three separate
+//! `Mutex`es, often in sequence on the same connection event — three
+//! distinct locks to get ordering right on, and three chances for some
+//! future call site to read one without the others and quietly desync the
+//! in-memory peer map from libp2p's actual connection set. `PeerManager`
+//! collapses them into a single guarded struct with a minimal mutation
+//! surface, so there's only one lock to take and one place that's allowed
+//! to write to any of the three.
+
+use crate::{now_ms, PeerInfo, ScoutPenaltyBook, ScoutPenaltyStatus, ScoutPenaltyUpdate};
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub struct PeerManager {
+    peers: HashMap<String, PeerInfo>,
+    known: Vec<String>,
+    penalties: ScoutPenaltyBook,
+}
+
+impl PeerManager {
+    pub fn new(peers: HashMap<String, PeerInfo>, known: Vec<String>, penalties: ScoutPenaltyBook) -> Self {
+        Self { peers, known, penalties }
+    }
+
+    /// Read-only view of connected peers, for helpers (`dispatch::rank_candidates`,
+    /// `connection_budget::lowest_value_inbound_peer`) that only rank already-tracked
+    /// entries and have no business mutating them.
+    pub fn peers(&self) -> &HashMap<String, PeerInfo> {
+        &self.peers
+    }
+
+    pub fn known(&self) -> &[String] {
+        &self.known
+    }
+
+    pub fn known_mut(&mut self) -> &mut Vec<String> {
+        &mut self.known
+    }
+
+    pub fn penalties_mut(&mut self) -> &mut ScoutPenaltyBook {
+        &mut self.penalties
+    }
+
+    pub fn penalties(&self) -> &ScoutPenaltyBook {
+        &self.penalties
+    }
+
+    /// Single mutation point for a peer's connection lifecycle: inserts a
+    /// freshly-connected peer with sane defaults if `peer_id` isn't tracked
+    /// yet, then hands `update` the (new-or-existing) `PeerInfo` to apply
+    /// whatever changed — connection open, verification, reported
+    /// capacity/load, hole-punch outcome. Every write to a tracked peer's
+    /// fields goes through here or `record_score` (gossipsub score) instead
+    /// of callers reaching into the map directly.
+    pub fn update_connection_state(
+        &mut self,
+        peer_id: &str,
+        update: impl FnOnce(&mut PeerInfo),
+    ) -> &mut PeerInfo {
+        let entry = self.peers.entry(peer_id.to_string()).or_insert_with(|| PeerInfo {
+            peer_id: peer_id.to_string(),
+            connected_at: now_ms(),
+            last_seen_at: now_ms(),
+            addrs: Vec::new(),
+            verified: false,
+            handshake_failures: 0,
+            reported_capacity: None,
+            reported_load: None,
+            score: None,
+            hole_punch_succeeded: None,
+            inbound: false,
+        });
+        update(entry);
+        entry
+    }
+
+    /// Drop a disconnected peer's connection state. Its reputation in
+    /// `penalties` is untouched — a scout's history should survive it
+    /// going offline and reconnecting, not reset on every churn.
+    pub fn remove_peer(&mut self, peer_id: &str) -> Option<PeerInfo> {
+        self.peers.remove(peer_id)
+    }
+
+    /// Single mutation point for scout reputation: every accept/reject
+    /// observation flows through here rather than callers reaching into
+    /// `penalties` directly, so `ScoutPenaltyBook::apply_update`'s
+    /// decay/escalation invariants can't be bypassed by a call site that
+    /// forgets a step.
+    pub fn report_peer(&mut self, update: ScoutPenaltyUpdate) -> ScoutPenaltyStatus {
+        self.penalties.apply_update(update)
+    }
+
+    /// Single mutation point for the periodic gossipsub peer-score refresh
+    /// (`score_tick`). No-op if the peer has since disconnected.
+    pub fn record_score(&mut self, peer_id: &str, score: f64) {
+        if let Some(info) = self.peers.get_mut(peer_id) {
+            info.score = Some(score);
+        }
+    }
+
+    /// `true` if `peer_id` is currently blackholed. Callers that used to
+    /// take a separate `scout_penalties` lock just for this now read it off
+    /// the one lock they already hold. Mutates on ban expiry — see
+    /// `ScoutPenaltyBook::is_blackholed` — so callers that need a true pure
+    /// read (e.g. the transport-layer connection gate) should use
+    /// `is_banned` instead.
+    pub fn is_blackholed(&mut self, peer_id: &str) -> bool {
+        self.penalties.is_blackholed(peer_id)
+    }
+
+    /// Pure read against the combined struct: `true` if `peer_id` is
+    /// currently within its ban window, with no probation-reset side
+    /// effect on expiry. Used by `should_reject_peer_connection`, which
+    /// only needs to gate the connection and shouldn't need a mutable
+    /// borrow of the whole `PeerManager` to do it.
+    pub fn is_banned(&self, peer_id: &str) -> bool {
+        self.penalties.is_banned(peer_id)
+    }
+}
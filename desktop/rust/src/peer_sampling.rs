@@ -0,0 +1,187 @@
+//! Basalt-style Byzantine-resistant random peer sampling.
+//!
+//! Kademlia plus the flat `known_peers` list gives every peer an equal shot
+//! at steering who we dial and who work gets fanned out to, which lets a
+//! Sybil flood a disproportionate number of IDs into that set. This module
+//! maintains a small, unbiased "view" of `m` slots, each independently
+//! occupied by whichever observed peer currently minimizes a pseudorandom
+//! hash of that peer's id salted with the slot's own seed. An attacker can't
+//! steer which IDs win a slot without controlling a large fraction of the ID
+//! space, and periodically redrawing a slot's seed keeps the view from
+//! calcifying around whoever happened to win first.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Number of slots in the view. Each slot is an independent random draw, so
+/// more slots means a broader (and more Sybil-resistant) sample at the cost
+/// of more push/pull bandwidth.
+pub const VIEW_SIZE: usize = 32;
+
+/// Probability, per push/pull round, that any given slot's seed is redrawn.
+const SEED_REDRAW_PROBABILITY: f64 = 0.02;
+
+/// A candidate peer as exchanged over the push/pull protocol.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct SampledPeer {
+    pub peer_id: String,
+    pub addr: String,
+}
+
+impl SampledPeer {
+    /// Coarse bucket used so a single subnet can't fill many slots at once:
+    /// the first two octets of the embedded IPv4 address, or the literal
+    /// address string for anything else (IPv6, unparsed multiaddrs).
+    fn ip_bucket(&self) -> String {
+        for segment in self.addr.split('/') {
+            let octets: Vec<&str> = segment.split('.').collect();
+            if octets.len() == 4 && octets.iter().all(|o| o.parse::<u8>().is_ok()) {
+                return format!("{}.{}", octets[0], octets[1]);
+            }
+        }
+        self.addr.clone()
+    }
+}
+
+struct Slot {
+    seed: [u8; 32],
+    occupant: Option<SampledPeer>,
+    rank: Option<[u8; 32]>,
+}
+
+impl Slot {
+    fn fresh() -> Self {
+        Self {
+            seed: rand::thread_rng().gen(),
+            occupant: None,
+            rank: None,
+        }
+    }
+
+    fn rank_of(&self, peer: &SampledPeer) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.seed);
+        hasher.update(peer.peer_id.as_bytes());
+        *hasher.finalize().as_bytes()
+    }
+}
+
+/// The sampled view: `VIEW_SIZE` independent slots, each minimizing
+/// `blake3(seed_i || peer_id)` over all candidates it has seen.
+pub struct SamplingView {
+    slots: Vec<Slot>,
+}
+
+impl SamplingView {
+    pub fn new() -> Self {
+        Self {
+            slots: (0..VIEW_SIZE).map(|_| Slot::fresh()).collect(),
+        }
+    }
+
+    /// Offer a candidate peer to every slot; a slot keeps it only if its rank
+    /// under that slot's seed is lower than the current occupant's, and only
+    /// if doing so wouldn't push that slot's IP bucket occupancy above the
+    /// one-subnet-per-few-slots cap enforced by `bucket_cap`.
+    pub fn observe(&mut self, candidate: SampledPeer) {
+        let bucket_cap = (VIEW_SIZE / 8).max(1);
+        let candidate_bucket = candidate.ip_bucket();
+
+        for i in 0..self.slots.len() {
+            let slot = &self.slots[i];
+            if slot
+                .occupant
+                .as_ref()
+                .is_some_and(|occupant| occupant.peer_id == candidate.peer_id)
+            {
+                continue;
+            }
+
+            let candidate_rank = slot.rank_of(&candidate);
+            let wins = match (&slot.occupant, &slot.rank) {
+                (Some(_), Some(current_rank)) => candidate_rank < *current_rank,
+                _ => true,
+            };
+            if !wins {
+                continue;
+            }
+
+            // Exclude this slot itself: if it already holds a same-bucket
+            // occupant, replacing that occupant with another peer from the
+            // same bucket doesn't change the bucket's total occupancy, so it
+            // shouldn't be counted against its own cap.
+            let bucket_occupancy = self
+                .slots
+                .iter()
+                .enumerate()
+                .filter(|(j, s)| {
+                    *j != i
+                        && s.occupant
+                            .as_ref()
+                            .is_some_and(|o| o.ip_bucket() == candidate_bucket)
+                })
+                .count();
+            if bucket_occupancy >= bucket_cap {
+                continue;
+            }
+
+            let slot = &mut self.slots[i];
+            slot.occupant = Some(candidate.clone());
+            slot.rank = Some(candidate_rank);
+        }
+    }
+
+    /// Redraw each slot's seed with low, independent probability, dropping
+    /// its occupant so the view can't get stuck on an early winner forever.
+    pub fn maybe_redraw(&mut self) {
+        let mut rng = rand::thread_rng();
+        for slot in &mut self.slots {
+            if rng.gen_bool(SEED_REDRAW_PROBABILITY) {
+                slot.seed = rng.gen();
+                slot.occupant = None;
+                slot.rank = None;
+            }
+        }
+    }
+
+    pub fn sample(&self) -> Vec<SampledPeer> {
+        self.slots
+            .iter()
+            .filter_map(|slot| slot.occupant.clone())
+            .collect()
+    }
+
+    pub fn random_peer(&self) -> Option<SampledPeer> {
+        let view = self.sample();
+        if view.is_empty() {
+            return None;
+        }
+        let idx = rand::thread_rng().gen_range(0..view.len());
+        Some(view[idx].clone())
+    }
+
+    /// Fold a locally-observed candidate set and a peer's reported view into
+    /// this view's slot updates — the push/pull exchange's merge step.
+    pub fn merge(&mut self, candidates: impl IntoIterator<Item = SampledPeer>) {
+        let mut seen = HashMap::new();
+        for candidate in candidates {
+            seen.entry(candidate.peer_id.clone()).or_insert(candidate);
+        }
+        for candidate in seen.into_values() {
+            self.observe(candidate);
+        }
+    }
+}
+
+impl Default for SamplingView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Push/pull exchange request: "here's my view, send me yours."
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingPushPull {
+    pub view: Vec<SampledPeer>,
+}
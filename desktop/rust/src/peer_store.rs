@@ -0,0 +1,238 @@
+//! SQLite-backed peer reputation and metadata store, so a scout we've
+//! blackholed for misbehaving doesn't come back clean after a restart.
+//! `known_peers.json` only ever remembered a flat, deduped address list;
+//! this store keeps one row per peer across two tables — `peer_metadata`
+//! (first/last seen, addresses, verification status) and `peer_penalties`
+//! (accepted/failure counts, current score, blackhole-until time) — plus an
+//! `ip_bans` table of auto-escalated IP bans, and is
+//! queried behind an async pool so writes from the swarm loop never block
+//! on disk I/O.
+
+use anyhow::{Context, Result};
+use sqlx::sqlite::{SqlitePoolOptions, SqliteRow};
+use sqlx::{Row, SqlitePool};
+use std::path::Path;
+
+/// A peer's durable identity/connectivity metadata, independent of the
+/// ephemeral capacity/load/score fields tracked only in-memory on `PeerInfo`
+/// while the peer is actually connected.
+#[derive(Debug, Clone)]
+pub struct PeerMetadataRecord {
+    pub peer_id: String,
+    pub first_seen_ms: u128,
+    pub last_seen_ms: u128,
+    pub addrs: Vec<String>,
+    pub verified: bool,
+    pub handshake_failures: u32,
+}
+
+/// A peer's durable reputation, mirroring the fields `ScoutPenaltyBook`
+/// tracks in memory so a ban survives a restart.
+#[derive(Debug, Clone)]
+pub struct PenaltyRecord {
+    pub peer_id: String,
+    pub accepted_count: u32,
+    pub failure_count: u32,
+    /// Time-decayed reputation score on a 0-100 scale, baselined at 50.
+    pub score: f32,
+    pub banned_until_ms: Option<u128>,
+    pub last_reason: Option<String>,
+    /// Count of bans this peer has served, mirroring
+    /// `ScoutReputationEntry::recent_bans` — restored on rehydrate so a
+    /// repeat offender's next ban keeps escalating instead of resetting to
+    /// the base cooldown after a restart.
+    pub recent_bans: u32,
+}
+
+/// A temporary, auto-escalated IP ban (`ip_blacklist::IpBlacklist`'s
+/// config-loaded static entries aren't persisted — only escalations are).
+#[derive(Debug, Clone)]
+pub struct IpBanRecord {
+    pub ip: String,
+    pub banned_until_ms: u128,
+}
+
+/// Async handle onto the on-disk peer reputation/metadata database.
+pub struct PeerStore {
+    pool: SqlitePool,
+}
+
+impl PeerStore {
+    /// Open (creating if absent) the SQLite database at `path` and ensure
+    /// its schema exists.
+    pub async fn open(path: &Path) -> Result<Self> {
+        let url = format!("sqlite://{}?mode=rwc", path.display());
+        let pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect(&url)
+            .await
+            .with_context(|| format!("opening peer store at {}", path.display()))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS peer_metadata (
+                peer_id TEXT PRIMARY KEY,
+                first_seen_ms TEXT NOT NULL,
+                last_seen_ms TEXT NOT NULL,
+                addrs TEXT NOT NULL,
+                verified INTEGER NOT NULL,
+                handshake_failures INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS peer_penalties (
+                peer_id TEXT PRIMARY KEY,
+                accepted_count INTEGER NOT NULL,
+                failure_count INTEGER NOT NULL,
+                score REAL NOT NULL,
+                banned_until_ms TEXT,
+                last_reason TEXT,
+                recent_bans INTEGER NOT NULL DEFAULT 0
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS ip_bans (
+                ip TEXT PRIMARY KEY,
+                banned_until_ms TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(Self { pool })
+    }
+
+    /// Write-through a peer's metadata row, inserting it on first contact
+    /// and bumping `last_seen_ms`/`addrs`/`verified` on every subsequent
+    /// connection open/close.
+    pub async fn upsert_peer_metadata(&self, record: &PeerMetadataRecord) -> Result<()> {
+        let addrs_json = serde_json::to_string(&record.addrs)?;
+        sqlx::query(
+            "INSERT INTO peer_metadata
+                (peer_id, first_seen_ms, last_seen_ms, addrs, verified, handshake_failures)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(peer_id) DO UPDATE SET
+                last_seen_ms = excluded.last_seen_ms,
+                addrs = excluded.addrs,
+                verified = excluded.verified,
+                handshake_failures = excluded.handshake_failures",
+        )
+        .bind(&record.peer_id)
+        .bind(record.first_seen_ms.to_string())
+        .bind(record.last_seen_ms.to_string())
+        .bind(addrs_json)
+        .bind(record.verified)
+        .bind(record.handshake_failures)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Write-through a peer's reputation row, called after every
+    /// `ScoutPenaltyBook::apply_update`.
+    pub async fn upsert_penalty(&self, record: &PenaltyRecord) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO peer_penalties
+                (peer_id, accepted_count, failure_count, score, banned_until_ms, last_reason, recent_bans)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(peer_id) DO UPDATE SET
+                accepted_count = excluded.accepted_count,
+                failure_count = excluded.failure_count,
+                score = excluded.score,
+                banned_until_ms = excluded.banned_until_ms,
+                last_reason = excluded.last_reason,
+                recent_bans = excluded.recent_bans",
+        )
+        .bind(&record.peer_id)
+        .bind(record.accepted_count)
+        .bind(record.failure_count)
+        .bind(record.score)
+        .bind(record.banned_until_ms.map(|ms| ms.to_string()))
+        .bind(&record.last_reason)
+        .bind(record.recent_bans)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    fn metadata_from_row(row: &SqliteRow) -> Result<PeerMetadataRecord> {
+        let addrs_json: String = row.try_get("addrs")?;
+        let first_seen_ms: String = row.try_get("first_seen_ms")?;
+        let last_seen_ms: String = row.try_get("last_seen_ms")?;
+        Ok(PeerMetadataRecord {
+            peer_id: row.try_get("peer_id")?,
+            first_seen_ms: first_seen_ms.parse().unwrap_or(0),
+            last_seen_ms: last_seen_ms.parse().unwrap_or(0),
+            addrs: serde_json::from_str(&addrs_json).unwrap_or_default(),
+            verified: row.try_get("verified")?,
+            handshake_failures: row.try_get::<i64, _>("handshake_failures")? as u32,
+        })
+    }
+
+    fn penalty_from_row(row: &SqliteRow) -> Result<PenaltyRecord> {
+        let banned_until_ms: Option<String> = row.try_get("banned_until_ms")?;
+        Ok(PenaltyRecord {
+            peer_id: row.try_get("peer_id")?,
+            accepted_count: row.try_get::<i64, _>("accepted_count")? as u32,
+            failure_count: row.try_get::<i64, _>("failure_count")? as u32,
+            score: row.try_get("score")?,
+            banned_until_ms: banned_until_ms.and_then(|ms| ms.parse().ok()),
+            last_reason: row.try_get("last_reason")?,
+            recent_bans: row.try_get::<i64, _>("recent_bans")? as u32,
+        })
+    }
+
+    /// All known peer metadata, for rehydrating the `peers` map on startup.
+    pub async fn load_all_peer_metadata(&self) -> Result<Vec<PeerMetadataRecord>> {
+        let rows = sqlx::query("SELECT * FROM peer_metadata")
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(Self::metadata_from_row).collect()
+    }
+
+    /// All known peer reputation rows, for rehydrating `ScoutPenaltyBook`
+    /// on startup.
+    pub async fn load_all_penalties(&self) -> Result<Vec<PenaltyRecord>> {
+        let rows = sqlx::query("SELECT * FROM peer_penalties")
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(Self::penalty_from_row).collect()
+    }
+
+    /// Write-through an auto-escalated IP ban, called whenever a peer_id
+    /// gets blackholed and its known addresses are escalated.
+    pub async fn upsert_ip_ban(&self, record: &IpBanRecord) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO ip_bans (ip, banned_until_ms) VALUES (?1, ?2)
+             ON CONFLICT(ip) DO UPDATE SET banned_until_ms = excluded.banned_until_ms",
+        )
+        .bind(&record.ip)
+        .bind(record.banned_until_ms.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// All auto-escalated IP bans, for rehydrating `IpBlacklist` on startup.
+    /// Expired entries are returned too — `IpBlacklist::is_blocked` treats
+    /// them as unblocked and evicts them lazily.
+    pub async fn load_all_ip_bans(&self) -> Result<Vec<IpBanRecord>> {
+        let rows = sqlx::query("SELECT * FROM ip_bans")
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter()
+            .map(|row| -> Result<IpBanRecord> {
+                let banned_until_ms: String = row.try_get("banned_until_ms")?;
+                Ok(IpBanRecord {
+                    ip: row.try_get("ip")?,
+                    banned_until_ms: banned_until_ms.parse().unwrap_or(0),
+                })
+            })
+            .collect()
+    }
+}
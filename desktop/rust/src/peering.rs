@@ -0,0 +1,134 @@
+//! Full-mesh peering manager, modeled on netapp's fullmesh peering: every
+//! address in `known_peers` is treated as a connection that should be kept
+//! alive, with its own connection state and exponential-backoff reconnect
+//! schedule, instead of one global timer that blindly redials everything
+//! every `reconnect_seconds`.
+
+use crate::now_ms;
+use serde::Serialize;
+use std::collections::HashMap;
+
+const INITIAL_BACKOFF_MS: u64 = 5_000;
+const MAX_BACKOFF_MS: u64 = 5 * 60_000;
+const RTT_SMOOTHING_ALPHA: f64 = 0.2;
+const FLAPPING_FAILURE_THRESHOLD: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkState {
+    Connecting,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerLink {
+    pub addr: String,
+    pub state: LinkState,
+    #[serde(skip)]
+    pub next_attempt_at_ms: u128,
+    #[serde(skip)]
+    pub backoff_ms: u64,
+    pub consecutive_failures: u32,
+    pub smoothed_rtt_ms: Option<f64>,
+}
+
+impl PeerLink {
+    fn new(addr: String) -> Self {
+        Self {
+            addr,
+            state: LinkState::Down,
+            next_attempt_at_ms: now_ms(),
+            backoff_ms: INITIAL_BACKOFF_MS,
+            consecutive_failures: 0,
+            smoothed_rtt_ms: None,
+        }
+    }
+
+    /// A link that has flapped this many times in a row without establishing
+    /// is worth demoting ahead of a stable one when scheduling/ranking peers.
+    pub fn is_flapping(&self) -> bool {
+        self.consecutive_failures >= FLAPPING_FAILURE_THRESHOLD
+    }
+}
+
+/// Tracks per-address connection state and drives exponential-backoff
+/// reconnect independently per peer rather than via one shared interval.
+#[derive(Default)]
+pub struct PeeringManager {
+    links: HashMap<String, PeerLink>,
+}
+
+impl PeeringManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn link_mut(&mut self, addr: &str) -> &mut PeerLink {
+        self.links
+            .entry(addr.to_string())
+            .or_insert_with(|| PeerLink::new(addr.to_string()))
+    }
+
+    pub fn mark_connecting(&mut self, addr: &str) {
+        self.link_mut(addr).state = LinkState::Connecting;
+    }
+
+    pub fn mark_up(&mut self, addr: &str) {
+        let link = self.link_mut(addr);
+        link.state = LinkState::Up;
+        link.consecutive_failures = 0;
+        link.backoff_ms = INITIAL_BACKOFF_MS;
+    }
+
+    /// Connection dropped or a dial attempt failed: grow the backoff
+    /// exponentially (capped) and schedule the next attempt.
+    pub fn mark_down(&mut self, addr: &str) {
+        let link = self.link_mut(addr);
+        link.state = LinkState::Down;
+        link.consecutive_failures = link.consecutive_failures.saturating_add(1);
+        link.backoff_ms = (link.backoff_ms.saturating_mul(2)).min(MAX_BACKOFF_MS);
+        link.next_attempt_at_ms = now_ms() + link.backoff_ms as u128;
+    }
+
+    /// Fold a ping round-trip sample into an exponentially-smoothed RTT.
+    pub fn record_rtt(&mut self, addr: &str, sample_ms: f64) {
+        let link = self.link_mut(addr);
+        link.smoothed_rtt_ms = Some(match link.smoothed_rtt_ms {
+            Some(prev) => prev + RTT_SMOOTHING_ALPHA * (sample_ms - prev),
+            None => sample_ms,
+        });
+    }
+
+    /// Addresses whose backoff has elapsed and that aren't up (a link still
+    /// marked connecting is offered again), stable-sorted non-flapping links first so the
+    /// reconnect loop burns its redial budget on links likely to actually
+    /// come up before it gets to ones that have been flapping.
+    pub fn due_for_reconnect(&mut self, known: &[String]) -> Vec<String> {
+        let now = now_ms();
+        for addr in known {
+            self.links
+                .entry(addr.clone())
+                .or_insert_with(|| PeerLink::new(addr.clone()));
+        }
+        let mut due: Vec<&PeerLink> = self
+            .links
+            .values()
+            .filter(|link| link.state != LinkState::Up && link.next_attempt_at_ms <= now)
+            .collect();
+        due.sort_by_key(|link| link.is_flapping());
+        due.into_iter().map(|link| link.addr.clone()).collect()
+    }
+
+    pub fn rtt_ms(&self, addr: &str) -> Option<f64> {
+        self.links.get(addr).and_then(|l| l.smoothed_rtt_ms)
+    }
+
+    pub fn link(&self, addr: &str) -> Option<&PeerLink> {
+        self.links.get(addr)
+    }
+
+    pub fn links(&self) -> Vec<PeerLink> {
+        self.links.values().cloned().collect()
+    }
+}
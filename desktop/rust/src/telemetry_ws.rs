@@ -2,44 +2,243 @@ use crate::{now_ms, SharedState};
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        Query, State,
+    },
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
     },
-    response::IntoResponse,
     routing::get,
-    Router,
+    Json, Router,
 };
-use futures::{SinkExt, StreamExt};
-use serde::Serialize;
-use std::{net::SocketAddr, time::Duration};
-use tokio::time;
+use futures::{stream::Stream, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{sync::Mutex, time};
+use tokio_stream::wrappers::IntervalStream;
+
+/// Bounded, age-expiring ring buffer of recent `TelemetrySnapshot`s shared
+/// between the background filler task, the `/telemetry/history` HTTP route,
+/// and the WS `replay_history` control message.
+#[derive(Clone)]
+pub struct TelemetryHistory {
+    buffer: Arc<Mutex<VecDeque<TelemetrySnapshot>>>,
+}
+
+impl TelemetryHistory {
+    const CAPACITY: usize = 300;
+    const MAX_AGE_MS: u128 = 15 * 60 * 1000;
+
+    pub fn new() -> Self {
+        Self {
+            buffer: Arc::new(Mutex::new(VecDeque::with_capacity(Self::CAPACITY))),
+        }
+    }
+
+    async fn push(&self, snapshot: TelemetrySnapshot) {
+        let mut buffer = self.buffer.lock().await;
+        let cutoff = now_ms().saturating_sub(Self::MAX_AGE_MS);
+        while buffer.front().is_some_and(|s| s.sampled_at_ms < cutoff) {
+            buffer.pop_front();
+        }
+        while buffer.len() >= Self::CAPACITY {
+            buffer.pop_front();
+        }
+        buffer.push_back(snapshot);
+    }
 
-#[derive(Debug, Serialize)]
+    async fn since(&self, since_ms: u128, limit: usize) -> Vec<TelemetrySnapshot> {
+        let buffer = self.buffer.lock().await;
+        buffer
+            .iter()
+            .filter(|s| s.sampled_at_ms >= since_ms)
+            .rev()
+            .take(limit)
+            .rev()
+            .cloned()
+            .collect()
+    }
+}
+
+impl Default for TelemetryHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 struct TelemetrySnapshot {
     connected_peers: usize,
     active_scouts: usize,
     global_tflops: f32,
+    bandwidth: crate::BandwidthStats,
     sampled_at_ms: u128,
 }
 
-pub fn spawn_telemetry_ws_server(state: SharedState, port: u16) {
+impl TelemetrySnapshot {
+    /// Name of each field as it appears in the client-facing `metrics` selector.
+    const FIELDS: [&'static str; 4] =
+        ["connected_peers", "active_scouts", "global_tflops", "bandwidth"];
+
+    /// Project this snapshot down to the requested field set, always keeping
+    /// `sampled_at_ms` so a client can tell how fresh a projected payload is.
+    fn project(&self, metrics: &[String]) -> serde_json::Value {
+        let full = serde_json::to_value(self).expect("TelemetrySnapshot always serializes");
+        if metrics.is_empty() {
+            return full;
+        }
+
+        let mut out = serde_json::Map::new();
+        if let serde_json::Value::Object(fields) = full {
+            for key in metrics {
+                if !Self::FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                if let Some(value) = fields.get(key.as_str()) {
+                    out.insert(key.clone(), value.clone());
+                }
+            }
+            out.insert(
+                "sampled_at_ms".to_string(),
+                serde_json::json!(self.sampled_at_ms),
+            );
+        }
+        serde_json::Value::Object(out)
+    }
+}
+
+/// Inbound control messages a client sends over `/telemetry/ws` to choose
+/// which fields it wants and how often, instead of the fixed global feed.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientControl {
+    Subscribe {
+        #[serde(default = "default_topic")]
+        topic: String,
+        #[serde(default)]
+        metrics: Vec<String>,
+        #[serde(default = "default_interval_ms")]
+        interval_ms: u64,
+    },
+    Unsubscribe {
+        id: u64,
+    },
+    ReplayHistory {
+        #[serde(default)]
+        since_ms: u128,
+        #[serde(default = "default_history_limit")]
+        limit: usize,
+    },
+}
+
+fn default_history_limit() -> usize {
+    100
+}
+
+fn default_interval_ms() -> u64 {
+    2000
+}
+
+fn default_topic() -> String {
+    "global".to_string()
+}
+
+struct Subscription {
+    id: u64,
+    topic: String,
+    metrics: Vec<String>,
+    interval_ms: u64,
+    next_fire_at_ms: u128,
+}
+
+/// Base polling granularity used to drive the heterogeneous per-subscription
+/// intervals off of a single timer instead of one `tokio::time::interval` per
+/// subscription.
+const SUBSCRIPTION_TICK_MS: u64 = 100;
+
+/// Paths to a PEM certificate chain and private key used to serve the
+/// telemetry endpoints as `https://`/`wss://` instead of plaintext.
+#[derive(Debug, Clone)]
+pub struct TelemetryTlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+}
+
+pub fn spawn_telemetry_ws_server(state: SharedState, port: u16, tls: Option<TelemetryTlsConfig>) {
     tokio::spawn(async move {
         let app = Router::new()
             .route("/telemetry/ws", get(telemetry_ws_handler))
-            .with_state(state);
+            .route("/telemetry/sse", get(telemetry_sse_handler))
+            .route("/telemetry/metrics", get(telemetry_metrics_handler))
+            .route("/telemetry/history", get(telemetry_history_handler))
+            .route("/telemetry/peers", get(telemetry_peers_handler))
+            .with_state(state.clone());
 
         let addr = SocketAddr::from(([0, 0, 0, 0], port));
-        tracing::info!(%addr, "telemetry websocket server starting");
 
-        let listener = tokio::net::TcpListener::bind(addr)
-            .await
-            .expect("failed to bind telemetry websocket port");
+        tokio::spawn(fill_history(state));
 
-        axum::serve(listener, app)
-            .await
-            .expect("telemetry websocket server crashed");
+        match tls {
+            Some(tls) => {
+                tracing::info!(%addr, "telemetry wss server starting");
+                let config = axum_server::tls_rustls::RustlsConfig::from_pem_file(
+                    &tls.cert_path,
+                    &tls.key_path,
+                )
+                .await
+                .expect("failed to load telemetry TLS cert/key");
+
+                axum_server::bind_rustls(addr, config)
+                    .serve(app.into_make_service())
+                    .await
+                    .expect("telemetry wss server crashed");
+            }
+            None => {
+                tracing::info!(%addr, "telemetry websocket server starting");
+                let listener = tokio::net::TcpListener::bind(addr)
+                    .await
+                    .expect("failed to bind telemetry websocket port");
+
+                axum::serve(listener, app)
+                    .await
+                    .expect("telemetry websocket server crashed");
+            }
+        }
     });
 }
 
+/// Fills `state.telemetry_history` on the same 2-second cadence as the
+/// default telemetry feed, independent of whether any client is connected,
+/// so a client that connects at time T can still see the prior minutes.
+async fn fill_history(state: SharedState) {
+    let mut ticker = time::interval(Duration::from_secs(2));
+    loop {
+        ticker.tick().await;
+        let snapshot = collect_snapshot(&state).await;
+        state.telemetry_history.push(snapshot).await;
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryQuery {
+    #[serde(default)]
+    since_ms: u128,
+    #[serde(default = "default_history_limit")]
+    limit: usize,
+}
+
+async fn telemetry_history_handler(
+    State(state): State<SharedState>,
+    Query(query): Query<HistoryQuery>,
+) -> Json<Vec<TelemetrySnapshot>> {
+    Json(
+        state
+            .telemetry_history
+            .since(query.since_ms, query.limit)
+            .await,
+    )
+}
+
 async fn telemetry_ws_handler(
     ws: WebSocketUpgrade,
     State(state): State<SharedState>,
@@ -47,39 +246,208 @@ async fn telemetry_ws_handler(
     ws.on_upgrade(move |socket| telemetry_stream(socket, state))
 }
 
+/// Server-Sent Events variant of the telemetry feed for browser dashboards and
+/// `curl`/`EventSource` consumers that don't want a WebSocket handshake. Emits
+/// the same 2-second `TelemetrySnapshot` the WS handler sends, as `text/event-stream`.
+async fn telemetry_sse_handler(
+    State(state): State<SharedState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let ticker = IntervalStream::new(time::interval(Duration::from_secs(2)));
+    let stream = ticker.then(move |_| {
+        let state = state.clone();
+        async move {
+            let snapshot = collect_snapshot(&state).await;
+            let event = match serde_json::to_string(&snapshot) {
+                Ok(payload) => Event::default().event("telemetry").data(payload),
+                Err(error) => {
+                    tracing::warn!(%error, "failed to serialize telemetry snapshot for SSE");
+                    Event::default().event("error").data("serialization failed")
+                }
+            };
+            Ok(event)
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
 async fn telemetry_stream(socket: WebSocket, state: SharedState) {
     let (mut sender, mut receiver) = socket.split();
-    let mut ticker = time::interval(Duration::from_secs(2));
+    let mut ticker = time::interval(Duration::from_millis(SUBSCRIPTION_TICK_MS));
+
+    // Clients that never send a `subscribe` control message keep getting the
+    // original unfiltered 2-second global feed under subscription id 0.
+    let mut subscriptions: Vec<Subscription> = vec![Subscription {
+        id: 0,
+        topic: default_topic(),
+        metrics: Vec::new(),
+        interval_ms: 2000,
+        next_fire_at_ms: now_ms(),
+    }];
+    let mut next_id: u64 = 1;
 
     loop {
         tokio::select! {
             Some(message) = receiver.next() => {
                 match message {
                     Ok(Message::Close(_)) => break,
+                    Ok(Message::Text(text)) => {
+                        match serde_json::from_str::<ClientControl>(&text) {
+                            Ok(ClientControl::Subscribe { topic, metrics, interval_ms }) => {
+                                // First explicit subscribe drops the implicit default feed.
+                                subscriptions.retain(|sub| sub.id != 0);
+                                let id = next_id;
+                                next_id += 1;
+                                subscriptions.push(Subscription {
+                                    id,
+                                    topic,
+                                    metrics,
+                                    interval_ms: interval_ms.max(SUBSCRIPTION_TICK_MS),
+                                    next_fire_at_ms: now_ms(),
+                                });
+                                let ack = serde_json::json!({"type": "subscribed", "id": id});
+                                if sender.send(Message::Text(ack.to_string())).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Ok(ClientControl::Unsubscribe { id }) => {
+                                subscriptions.retain(|sub| sub.id != id);
+                                let ack = serde_json::json!({"type": "unsubscribed", "id": id});
+                                if sender.send(Message::Text(ack.to_string())).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Ok(ClientControl::ReplayHistory { since_ms, limit }) => {
+                                let backlog = state.telemetry_history.since(since_ms, limit).await;
+                                let payload = serde_json::json!({"type": "history", "snapshots": backlog});
+                                if sender.send(Message::Text(payload.to_string())).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Err(error) => {
+                                tracing::debug!(%error, "ignoring malformed telemetry control message");
+                            }
+                        }
+                    }
                     Ok(_) => {}
                     Err(_) => break,
                 }
             }
             _ = ticker.tick() => {
-                let snapshot = collect_snapshot(&state).await;
-                let payload = match serde_json::to_string(&snapshot) {
-                    Ok(payload) => payload,
-                    Err(error) => {
-                        tracing::warn!(%error, "failed to serialize telemetry snapshot");
-                        continue;
+                let due: Vec<usize> = subscriptions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, sub)| now_ms() >= sub.next_fire_at_ms)
+                    .map(|(idx, _)| idx)
+                    .collect();
+                if due.is_empty() {
+                    continue;
+                }
+
+                // Only compute the snapshots a due subscription actually needs.
+                let needs_global = due.iter().any(|&idx| subscriptions[idx].topic != "peers");
+                let needs_peers = due.iter().any(|&idx| subscriptions[idx].topic == "peers");
+                let snapshot = if needs_global { Some(collect_snapshot(&state).await) } else { None };
+                let peer_stats = if needs_peers { Some(collect_peer_stats(&state).await) } else { None };
+
+                for idx in due {
+                    let sub = &mut subscriptions[idx];
+                    sub.next_fire_at_ms = now_ms() + sub.interval_ms as u128;
+
+                    let mut payload = if sub.topic == "peers" {
+                        serde_json::json!({
+                            "topic": "peers",
+                            "peers": peer_stats.as_ref().expect("peer stats computed for due peers subscription"),
+                            "sampled_at_ms": now_ms(),
+                        })
+                    } else {
+                        snapshot
+                            .as_ref()
+                            .expect("snapshot computed for due global subscription")
+                            .project(&sub.metrics)
+                    };
+                    if let serde_json::Value::Object(fields) = &mut payload {
+                        fields.insert("subscription_id".to_string(), serde_json::json!(sub.id));
                     }
-                };
+                    let Ok(text) = serde_json::to_string(&payload) else {
+                        tracing::warn!("failed to serialize projected telemetry snapshot");
+                        continue;
+                    };
 
-                if sender.send(Message::Text(payload)).await.is_err() {
-                    break;
+                    if sender.send(Message::Text(text)).await.is_err() {
+                        return;
+                    }
                 }
             }
         }
     }
 }
 
+/// Renders the same values `collect_snapshot` computes in Prometheus text
+/// exposition format, so operators can scrape this node with an existing
+/// Prometheus/Grafana stack instead of building a bespoke WS consumer.
+async fn telemetry_metrics_handler(State(state): State<SharedState>) -> impl IntoResponse {
+    let snapshot = collect_snapshot(&state).await;
+
+    let body = format!(
+        "# HELP shard_connected_peers Number of peers currently connected.\n\
+         # TYPE shard_connected_peers gauge\n\
+         shard_connected_peers {connected_peers}\n\
+         # HELP shard_active_scouts Number of connected peers that have completed verification.\n\
+         # TYPE shard_active_scouts gauge\n\
+         shard_active_scouts {active_scouts}\n\
+         # HELP shard_global_tflops Estimated aggregate network throughput in TFLOPS.\n\
+         # TYPE shard_global_tflops gauge\n\
+         shard_global_tflops {global_tflops}\n",
+        connected_peers = snapshot.connected_peers,
+        active_scouts = snapshot.active_scouts,
+        global_tflops = snapshot.global_tflops,
+    );
+
+    (
+        [("content-type", "text/plain; version=0.0.4")],
+        body,
+    )
+}
+
+/// Recursively walks any serializable per-peer record into a generic JSON
+/// value. Because this goes through `serde_json::Value` rather than a fixed
+/// struct, new per-peer metrics (nested structures or arrays) show up in the
+/// `/telemetry/peers` output automatically, without changing the route.
+fn to_stats_value<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+/// One record per connected peer — id, verified flag, last-seen age, and
+/// whatever capacity/load the peer has reported — for drill-down dashboards
+/// that need more than the four aggregate numbers in `TelemetrySnapshot`.
+async fn collect_peer_stats(state: &SharedState) -> Vec<serde_json::Value> {
+    let pm = state.peer_manager.lock().await;
+    let peers = pm.peers();
+    let now = now_ms();
+    peers
+        .values()
+        .map(|peer| {
+            let mut record = to_stats_value(peer);
+            if let serde_json::Value::Object(fields) = &mut record {
+                fields.insert(
+                    "last_seen_age_ms".to_string(),
+                    serde_json::json!(now.saturating_sub(peer.last_seen_at)),
+                );
+            }
+            record
+        })
+        .collect()
+}
+
+async fn telemetry_peers_handler(State(state): State<SharedState>) -> Json<serde_json::Value> {
+    let peers = collect_peer_stats(&state).await;
+    Json(serde_json::json!({ "peers": peers, "count": peers.len(), "sampled_at_ms": now_ms() }))
+}
+
 async fn collect_snapshot(state: &SharedState) -> TelemetrySnapshot {
-    let peers = state.peers.lock().await;
+    let pm = state.peer_manager.lock().await;
+    let peers = pm.peers();
     let connected_peers = peers.len();
     let active_scouts = peers.values().filter(|peer| peer.verified).count();
 
@@ -98,6 +466,7 @@ async fn collect_snapshot(state: &SharedState) -> TelemetrySnapshot {
         connected_peers,
         active_scouts,
         global_tflops: (estimated_tflops * 100.0).round() / 100.0,
+        bandwidth: state.bandwidth.stats(),
         sampled_at_ms: now_ms(),
     }
 }